@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    time::{Duration, SystemTime},
+};
+use uuid::Uuid;
+
+use crate::{instance, timer, utils};
+
+#[derive(Serialize)]
+struct ListedTimer {
+    id: Uuid,
+    name: String,
+    state: timer::State,
+    /// Seconds left as of the last save, the GUI doesn't write every tick
+    remaining: u64,
+    remaining_hms: String,
+}
+
+/// `oxyclock --list`: prints the timers of `profile` as JSON without opening a
+/// window. Only reads the state, so it's fine to run next to the GUI.
+pub fn list(profile: &str) {
+    println!("{}", to_json(&crate::load_timers(profile)));
+}
+
+/// How `--watch` writes the time left
+#[derive(Debug, Clone, Copy)]
+pub enum WatchFormat {
+    /// `01:05:09`, the default
+    Hms,
+    /// `65:09`, minutes keep counting past the hour
+    MinSec,
+}
+
+impl WatchFormat {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "hms" => Some(Self::Hms),
+            "mmss" => Some(Self::MinSec),
+            _ => None,
+        }
+    }
+
+    fn format(self, duration: Duration) -> String {
+        let secs = duration.as_secs();
+        match self {
+            Self::Hms => utils::format_hms(duration),
+            Self::MinSec => format!("{:02}:{:02}", secs / 60, secs % 60),
+        }
+    }
+}
+
+/// `oxyclock --watch [--format hms|mmss]`: for status bars like i3blocks, polybar
+/// or waybar. Prints the least time left on a running countdown of `profile` on
+/// its own line whenever it changes, and an empty line while nothing counts down
+/// so the bar can hide the block. Reads the saved state once a second like
+/// `--list`, running timers are caught up on the time since they were written.
+/// Runs until interrupted or the bar closes the pipe.
+pub fn watch(profile: &str, format: WatchFormat) {
+    let mut stdout = io::stdout().lock();
+    let mut last = None;
+    loop {
+        let now = SystemTime::now();
+        let left = crate::load_timers(profile)
+            .into_iter()
+            .filter(|timer| {
+                timer.state == timer::State::Running && timer.mode == timer::Mode::Countdown
+            })
+            .map(|mut timer| {
+                timer.catch_up(now);
+                timer.time
+            })
+            .min();
+        let line = left.map(|left| format.format(left)).unwrap_or_default();
+        if last.as_ref() != Some(&line) {
+            if writeln!(stdout, "{line}")
+                .and_then(|()| stdout.flush())
+                .is_err()
+            {
+                return;
+            }
+            last = Some(line);
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+}
+
+/// `oxyclock status`, `oxyclock start Tea`...: hands the request to the daemon
+/// and prints its reply
+pub fn control(request: &str) {
+    match instance::send(request) {
+        Ok(reply) if reply.is_empty() => eprintln!("the running instance isn't a daemon"),
+        Ok(reply) => println!("{}", reply.trim_end()),
+        Err(err) => eprintln!("failed to reach the daemon, is `oxyclock --daemon` running? {err}"),
+    }
+}
+
+pub fn to_json(timers: &[timer::Timer]) -> String {
+    let listed: Vec<ListedTimer> = timers
+        .iter()
+        .enumerate()
+        .map(|(position, timer)| {
+            let remaining = if timer.state == timer::State::Stopped {
+                timer.get_duration(timer::MAX_DURATION).unwrap_or_default()
+            } else {
+                timer.time
+            };
+            ListedTimer {
+                id: timer.id,
+                name: timer.display_name(position),
+                state: timer.state.clone(),
+                remaining: remaining.as_secs(),
+                remaining_hms: utils::format_hms(remaining),
+            }
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&listed).unwrap_or_else(|err| {
+        log::error!("failed to serialize timers: {err}");
+        String::from("[]")
+    })
+}
@@ -2,16 +2,67 @@ use iced::{
     alignment::{Horizontal, Vertical},
     border,
     widget::{
-        button, column, container, row, scrollable, text, text_input, Button, Container,
-        Scrollable, Text, TextInput,
+        button, column, container, horizontal_space, opaque, pick_list, row, scrollable, slider,
+        text, text_editor, text_input, toggler, tooltip, Button, Column, Container, Row,
+        Scrollable, Text, TextEditor, TextInput, Tooltip,
     },
-    Alignment, Border, Element, Font, Length, Shadow, Theme,
+    Alignment, Border, Color, Element, Font, Length, Shadow, Theme,
+};
+use std::{
+    collections::HashMap,
+    time::{Duration, SystemTime},
 };
 use uuid::Uuid;
 
-use crate::{Msg, Time};
+use crate::{
+    config::{
+        Action, AfterFinish, Config, NotificationTimeout, Percentage, SortOrder, ThemeChoice,
+        Urgency,
+    },
+    custom_theme,
+    i18n::{is_rtl, t, t_fmt},
+    stats,
+    templates::Template,
+    utils, Command, Msg, Page, Time,
+};
 
 const TEXT_SIZE: u16 = 50;
+const BREAK_MINUTES: [u64; 6] = [5, 10, 15, 20, 30, 45];
+const ALARM_REPEATS: [u32; 5] = [1, 2, 3, 5, 10];
+const WARNING_SECONDS: [u64; 5] = [5, 10, 15, 30, 60];
+const ROUND_MINUTES: [u64; 5] = [1, 5, 10, 15, 30];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Field {
+    Hours,
+    Minutes,
+    Seconds,
+    Name,
+}
+
+impl Field {
+    pub fn next(self) -> Self {
+        match self {
+            Self::Hours => Self::Minutes,
+            Self::Minutes => Self::Seconds,
+            Self::Seconds => Self::Name,
+            Self::Name => Self::Hours,
+        }
+    }
+
+    pub fn previous(self) -> Self {
+        match self {
+            Self::Hours => Self::Name,
+            Self::Minutes => Self::Hours,
+            Self::Seconds => Self::Minutes,
+            Self::Name => Self::Seconds,
+        }
+    }
+}
+
+pub fn input_id(timer_id: Uuid, field: Field) -> text_input::Id {
+    text_input::Id::new(format!("{timer_id}-{field:?}"))
+}
 
 #[derive(PartialEq)]
 pub enum CustomButtonType {
@@ -65,64 +116,270 @@ pub fn custom_button<'a>(
         })
 }
 
+/// Gives an icon-only control a textual label, shown as a tooltip on hover.
+/// iced has no accessibility tree yet, so screen readers don't get it.
+pub fn labelled<'a>(
+    content: impl Into<Element<'a, Msg>>,
+    label: impl Into<String>,
+) -> Tooltip<'a, Msg> {
+    tooltip(
+        content,
+        text(label.into()).size(12),
+        tooltip::Position::Bottom,
+    )
+    .gap(4)
+    .padding(6)
+    .style(container::rounded_box)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeMode {
+    Editing,
+    Running,
+    /// Running within the final warning seconds
+    Ending,
+    /// Stopped but not editable, like a stopwatch holding what it counted
+    Fixed,
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn time_container<'a>(
     timer_id: Uuid,
     name: &str,
+    fallback_name: &str,
     hours: String,
     minutes: String,
     seconds: String,
-    running: bool,
+    mode: TimeMode,
+    digit_entry: bool,
 ) -> Container<'a, Msg> {
+    // Digits read left to right in every language, so this row is never mirrored
+    let running = mode != TimeMode::Editing;
+    let ending = mode == TimeMode::Ending;
+    let name_locked = matches!(mode, TimeMode::Running | TimeMode::Ending);
+    if !running && digit_entry {
+        return container(
+            column![
+                digits_input(timer_id, &format!("{hours}:{minutes}:{seconds}")),
+                name_input(timer_id, name, fallback_name, name_locked)
+            ]
+            .spacing(10)
+            .align_x(Alignment::Center),
+        );
+    }
     let time_row = row![
         if running {
-            time_text(hours)
+            time_text(hours, ending)
         } else {
-            time_input(timer_id, &hours, Msg::Hours)
+            time_input(timer_id, Field::Hours, t("hours"), &hours, Msg::Hours)
         },
         text(":").size(TEXT_SIZE).align_x(Horizontal::Center),
         if running {
-            time_text(minutes)
+            time_text(minutes, ending)
         } else {
-            time_input(timer_id, &minutes, Msg::Minutes)
+            time_input(
+                timer_id,
+                Field::Minutes,
+                t("minutes"),
+                &minutes,
+                Msg::Minutes,
+            )
         },
         text(":").size(TEXT_SIZE).align_x(Horizontal::Center),
         if running {
-            time_text(seconds)
+            time_text(seconds, ending)
         } else {
-            time_input(timer_id, &seconds, Msg::Seconds)
+            time_input(
+                timer_id,
+                Field::Seconds,
+                t("seconds"),
+                &seconds,
+                Msg::Seconds,
+            )
         },
     ]
-    .height(70)
     .align_y(Vertical::Center);
+    // Stepper buttons and field names make the stopped row taller than the fixed
+    // running one
+    let time_row = if running {
+        time_row.height(70)
+    } else {
+        time_row
+    };
 
     container(
-        column![time_row, name_input(timer_id, name, true)]
-            .spacing(10)
-            .align_x(Alignment::Center),
+        column![
+            time_row,
+            name_input(timer_id, name, fallback_name, name_locked)
+        ]
+        .spacing(10)
+        .align_x(Alignment::Center),
     )
 }
 
-fn name_input<'a>(timer_id: Uuid, name: &str, disabled: bool) -> TextInput<'a, Msg> {
-    let input = text_input("Name", name)
+pub fn timer_options<'a>(
+    timer_id: Uuid,
+    notify: bool,
+    silent: bool,
+    after_finish: Option<Duration>,
+    alarm_repeats: u32,
+    note_open: bool,
+    stopwatch: bool,
+) -> Column<'a, Msg> {
+    let break_minutes = after_finish.map(|length| length.as_secs() / 60);
+    let break_row = row![toggler(after_finish.is_some())
+        .label(t("break_after"))
+        .text_size(12)
+        .on_toggle(move |enabled| {
+            Msg::Break((timer_id, enabled.then_some(Duration::from_secs(5 * 60))))
+        })]
+    .push_maybe(break_minutes.map(|minutes| {
+        pick_list(BREAK_MINUTES, Some(minutes), move |minutes| {
+            Msg::Break((timer_id, Some(Duration::from_secs(minutes * 60))))
+        })
+        .text_size(12)
+    }))
+    .push_maybe(break_minutes.map(|_| text(t("minutes_short")).size(12)))
+    .push(horizontal_space().width(10))
+    .push(text(t("alarm_plays")).size(12))
+    .push(
+        pick_list(ALARM_REPEATS, Some(alarm_repeats), move |repeats| {
+            Msg::AlarmRepeats((timer_id, repeats))
+        })
+        .text_size(12),
+    )
+    .spacing(10)
+    .align_y(Alignment::Center);
+
+    let options = row![
+        toggler(stopwatch)
+            .label(t("stopwatch"))
+            .text_size(12)
+            .on_toggle(move |stopwatch| Msg::Stopwatch((timer_id, stopwatch))),
+        toggler(notify)
+            .label(t("notification"))
+            .text_size(12)
+            .on_toggle(move |notify| Msg::Notify((timer_id, notify))),
+        toggler(silent)
+            .label(t("silent"))
+            .text_size(12)
+            .on_toggle(move |silent| Msg::Silent((timer_id, silent))),
+        custom_button(
+            text(if note_open { t("hide_note") } else { t("note") }).size(12),
+            CustomButtonType::Secondary,
+            Some(70f32),
+            Some(24f32),
+        )
+        .on_press(Msg::ToggleNote(timer_id)),
+    ]
+    .spacing(20)
+    .align_y(Alignment::Center);
+
+    column![options, break_row]
+        .spacing(10)
+        .align_x(Alignment::Center)
+}
+
+pub fn note_editor<'a>(timer_id: Uuid, content: &'a text_editor::Content) -> TextEditor<'a, Msg> {
+    text_editor(content)
+        .placeholder(t("note"))
+        .height(80)
+        .size(12)
+        .on_action(move |action| Msg::Note((timer_id, action)))
+}
+
+/// Comma separated times left to announce, empty for the defaults from the settings
+pub fn announce_input<'a>(timer_id: Uuid, input: String) -> TextInput<'a, Msg> {
+    text_input(t("announce_at"), &input)
+        .on_input(move |input| Msg::AnnounceAt((timer_id, input)))
+        .width(250f32)
+        .padding(6)
+        .size(12)
+}
+
+pub fn webhook_input<'a>(timer_id: Uuid, url: &str) -> TextInput<'a, Msg> {
+    text_input(t("webhook_url"), url)
+        .on_input(move |url| Msg::Webhook((timer_id, url)))
+        .width(250f32)
+        .padding(6)
+        .size(12)
+}
+
+/// Weekday toggles, Monday first, and the time of day the timer goes off
+pub fn schedule_editor<'a>(timer_id: Uuid, days: [bool; 7], time: String) -> Row<'a, Msg> {
+    const DAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+    let toggles = DAYS
+        .iter()
+        .enumerate()
+        .fold(row![].spacing(4), |toggles, (day, label)| {
+            toggles.push(
+                custom_button(
+                    text(t(label)).size(12),
+                    if days[day] {
+                        CustomButtonType::Primary
+                    } else {
+                        CustomButtonType::Secondary
+                    },
+                    Some(30f32),
+                    Some(24f32),
+                )
+                .on_press(Msg::ScheduleDay((timer_id, day))),
+            )
+        });
+
+    row![
+        toggles,
+        text_input("09:00", &time)
+            .on_input(move |time| Msg::ScheduleTime((timer_id, time)))
+            .width(60)
+            .padding(4)
+            .size(12),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)
+}
+
+/// An emoji, or the path of an image for the notification
+pub fn icon_input<'a>(timer_id: Uuid, icon: &str) -> TextInput<'a, Msg> {
+    text_input(t("icon_placeholder"), icon)
+        .on_input(move |icon| Msg::Icon((timer_id, icon)))
+        .width(250f32)
+        .padding(6)
+        .size(12)
+}
+
+/// Image paths only show up in notifications, so the card skips them
+pub fn icon_text<'a>(icon: &str) -> Option<Text<'a>> {
+    let is_path = icon.contains(std::path::MAIN_SEPARATOR);
+    (!is_path).then(|| text(icon.to_string()).size(28))
+}
+
+fn name_input<'a>(
+    timer_id: Uuid,
+    name: &str,
+    fallback_name: &str,
+    disabled: bool,
+) -> TextInput<'a, Msg> {
+    let input = text_input(fallback_name, name)
+        .id(input_id(timer_id, Field::Name))
         .width(250f32)
         .padding(8)
         .size(12)
         .style(|theme: &Theme, _| {
             let palette = theme.palette();
             text_input::Style {
-                background: theme
-                    .extended_palette()
-                    .secondary
-                    .weak
-                    .color
-                    .scale_alpha(0.1)
-                    .into(),
+                background: custom_theme::faded(
+                    theme,
+                    theme.extended_palette().secondary.weak.color,
+                    0.1,
+                )
+                .into(),
                 border: Border::default()
                     .rounded(8)
                     .width(1)
-                    .color(palette.background.scale_alpha(0.5)),
+                    .color(custom_theme::border_color(theme)),
                 icon: palette.text,
-                placeholder: palette.text.scale_alpha(0.3),
+                placeholder: custom_theme::faded_text(theme, 0.3),
                 value: palette.text,
                 selection: palette.primary.scale_alpha(0.7),
             }
@@ -134,37 +391,82 @@ fn name_input<'a>(timer_id: Uuid, name: &str, disabled: bool) -> TextInput<'a, M
     }
 }
 
-fn time_input<'a, F>(timer_id: Uuid, value: &str, msg: F) -> Container<'a, Msg>
+/// Field with stepper buttons around it and its name spelled out underneath,
+/// the placeholder never shows since the field always holds digits
+fn time_input<'a, F>(
+    timer_id: Uuid,
+    field: Field,
+    label: &'static str,
+    value: &str,
+    msg: F,
+) -> Container<'a, Msg>
 where
     F: 'static + Fn(Time) -> Msg,
 {
+    let width = digits_width(value);
+    // Flags the field Start would reject before it's even pressed
+    let invalid = value.trim().parse::<u64>().is_err();
+    let stepper = |up: bool| {
+        let step = msg(Time {
+            id: timer_id,
+            time: step_value(field, value, up),
+        });
+        labelled(
+            custom_button(
+                text(if up { "▲" } else { "▼" }).size(10),
+                CustomButtonType::Secondary,
+                Some(width),
+                Some(18f32),
+            )
+            .on_press(step),
+            t_fmt(
+                if up { "increase" } else { "decrease" },
+                &[("field", label)],
+            ),
+        )
+    };
+    let increase = stepper(true);
+    let decrease = stepper(false);
+
     container(
-        text_input("", value)
+        column![
+            increase,
+            text_input(label, value)
+                .id(input_id(timer_id, field))
+                .on_submit(Msg::Start(timer_id))
+                .align_x(Horizontal::Center)
+                .width(width)
+                .size(TEXT_SIZE)
+                .style(move |theme: &Theme, _| time_input_style(theme, invalid))
+                .on_input(move |value| {
+                    msg(Time {
+                        id: timer_id,
+                        time: value,
+                    })
+                }),
+            decrease,
+            text(label)
+                .size(10)
+                .width(width)
+                .align_x(Horizontal::Center),
+        ]
+        .spacing(4),
+    )
+}
+
+/// The whole duration in one field, typed digits shift in from the right.
+/// Takes the id of the hours field so focusing and the keypad still find it.
+fn digits_input<'a>(timer_id: Uuid, value: &str) -> Container<'a, Msg> {
+    container(
+        text_input("00:00:00", value)
+            .id(input_id(timer_id, Field::Hours))
+            .on_submit(Msg::Start(timer_id))
             .align_x(Horizontal::Center)
-            .width(70)
+            .width(digits_width(value))
             .size(TEXT_SIZE)
-            .style(|theme: &Theme, _| {
-                let palette = theme.palette();
-                text_input::Style {
-                    background: theme
-                        .extended_palette()
-                        .secondary
-                        .weak
-                        .color
-                        .scale_alpha(0.1)
-                        .into(),
-                    border: Border::default()
-                        .rounded(8)
-                        .width(1)
-                        .color(palette.background.scale_alpha(0.5)),
-                    icon: palette.text,
-                    placeholder: palette.text.scale_alpha(0.3),
-                    value: palette.text,
-                    selection: palette.primary.scale_alpha(0.7),
-                }
-            })
+            .style(|theme: &Theme, _| time_input_style(theme, false))
             .on_input(move |value| {
-                msg(Time {
+                Msg::Digits(Time {
                     id: timer_id,
                     time: value,
                 })
@@ -172,10 +474,131 @@ where
     )
 }
 
-fn time_text<'a>(t: String) -> Container<'a, Msg> {
+fn time_input_style(theme: &Theme, invalid: bool) -> text_input::Style {
+    let palette = theme.palette();
+    text_input::Style {
+        background: custom_theme::faded(theme, theme.extended_palette().secondary.weak.color, 0.1)
+            .into(),
+        border: Border::default().rounded(8).width(1).color(if invalid {
+            palette.danger
+        } else {
+            custom_theme::border_color(theme)
+        }),
+        icon: palette.text,
+        placeholder: custom_theme::faded_text(theme, 0.3),
+        value: palette.text,
+        selection: palette.primary.scale_alpha(0.7),
+    }
+}
+
+/// Minutes and seconds wrap around without carrying, hours only stop at zero
+fn step_value(field: Field, value: &str, up: bool) -> String {
+    let value = value.trim().parse::<u64>().unwrap_or(0);
+    let stepped = match (field, up) {
+        (Field::Hours, true) => value.saturating_add(1),
+        (Field::Hours, false) => value.saturating_sub(1),
+        (_, true) => (value.min(59) + 1) % 60,
+        (_, false) => (value.min(59) + 59) % 60,
+    };
+    format!("{stepped:02}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum KeypadKey {
+    Digit(u8),
+    Backspace,
+    Clear,
+}
+
+/// Applies a keypad press to a time field. Digits shift in from the right once
+/// the field is full, and minutes and seconds stop at 59.
+pub fn keypad_value(field: Field, value: &str, key: KeypadKey) -> String {
+    let digits: String = value.chars().filter(char::is_ascii_digit).collect();
+    let max_digits = if field == Field::Hours { 3 } else { 2 };
+    let digits = match key {
+        KeypadKey::Digit(digit) => {
+            let digits = format!("{}{digit}", digits.trim_start_matches('0'));
+            digits[digits.len().saturating_sub(max_digits)..].to_string()
+        }
+        KeypadKey::Backspace => digits[..digits.len().saturating_sub(1)].to_string(),
+        KeypadKey::Clear => String::new(),
+    };
+
+    let value = digits.parse::<u64>().unwrap_or(0);
+    let value = if field == Field::Hours {
+        value
+    } else {
+        value.min(59)
+    };
+    format!("{value:02}")
+}
+
+/// On-screen digits for touch mode, typing into `field` of the timer
+pub fn keypad<'a>(timer_id: Uuid, field: Field) -> Column<'a, Msg> {
+    let key = |label: String, key: KeypadKey| {
+        custom_button(
+            text(label).size(16),
+            CustomButtonType::Secondary,
+            Some(50f32),
+            Some(40f32),
+        )
+        .on_press(Msg::Keypad((timer_id, key)))
+    };
+    let field_button = |label: &'static str, target: Field| {
+        custom_button(
+            text(t(label)).size(12),
+            if field == target {
+                CustomButtonType::Primary
+            } else {
+                CustomButtonType::Secondary
+            },
+            Some(70f32),
+            Some(30f32),
+        )
+        .on_press(Msg::KeypadField((timer_id, target)))
+    };
+
+    let digits =
+        [[1, 2, 3], [4, 5, 6], [7, 8, 9]]
+            .into_iter()
+            .fold(column![].spacing(6), |rows, digits| {
+                rows.push(digits.into_iter().fold(row![].spacing(6), |row, digit| {
+                    row.push(key(digit.to_string(), KeypadKey::Digit(digit)))
+                }))
+            });
+
+    column![
+        row![
+            field_button("hours", Field::Hours),
+            field_button("minutes", Field::Minutes),
+            field_button("seconds", Field::Seconds),
+        ]
+        .spacing(6),
+        digits,
+        row![
+            key(t("clear").to_string(), KeypadKey::Clear),
+            key("0".to_string(), KeypadKey::Digit(0)),
+            key("⌫".to_string(), KeypadKey::Backspace),
+        ]
+        .spacing(6),
+    ]
+    .spacing(6)
+    .align_x(Alignment::Center)
+}
+
+/// Two digits fit the default width, hours past 99 widen their field
+fn digits_width(value: &str) -> f32 {
+    70.0 + 30.0 * value.chars().count().saturating_sub(2) as f32
+}
+
+fn time_text<'a>(t: String, ending: bool) -> Container<'a, Msg> {
+    let width = digits_width(&t);
     container(
         text(t)
-            .width(70)
+            .style(move |theme: &Theme| text::Style {
+                color: ending.then(|| theme.palette().danger),
+            })
+            .width(width)
             .height(70f32 + text_input::DEFAULT_PADDING.top)
             .size(TEXT_SIZE)
             .align_y(Alignment::Center)
@@ -184,30 +607,1159 @@ fn time_text<'a>(t: String) -> Container<'a, Msg> {
     .style(|theme: &Theme| container::Style {
         text_color: None,
         background: Some(
-            theme
-                .extended_palette()
-                .secondary
-                .weak
-                .color
-                .scale_alpha(0.1)
-                .into(),
+            custom_theme::faded(theme, theme.extended_palette().secondary.weak.color, 0.1).into(),
         ),
         border: Border::default()
             .rounded(8)
             .width(1)
-            .color(theme.palette().background.scale_alpha(0.5)),
+            .color(custom_theme::border_color(theme)),
         shadow: Shadow::default(),
     })
 }
 
-pub fn top_bar<'a>() -> Container<'a, Msg> {
+pub fn percentage_text<'a>(remaining: u64, shown: Percentage) -> Text<'a> {
+    let label = match shown {
+        Percentage::Remaining => t_fmt("percent_remaining", &[("percent", &remaining.to_string())]),
+        Percentage::Done => t_fmt(
+            "percent_done",
+            &[("percent", &(100 - remaining).to_string())],
+        ),
+    };
+    text(label).size(14).align_x(Horizontal::Center)
+}
+
+pub fn done_badge<'a>() -> Container<'a, Msg> {
+    container(text(t("done")).size(12))
+        .padding([4, 10])
+        .style(|theme: &Theme| {
+            let success = theme.extended_palette().success.strong;
+            container::Style {
+                background: Some(success.color.into()),
+                text_color: Some(success.text),
+                border: border::rounded(8.0),
+                ..container::Style::default()
+            }
+        })
+}
+
+/// Doesn't block anything, two timers may share a name on purpose
+pub fn duplicate_name_badge<'a>() -> Container<'a, Msg> {
+    container(text(t("duplicate_name")).size(12))
+        .padding([4, 10])
+        .style(|theme: &Theme| container::Style {
+            background: Some(custom_theme::faded(theme, theme.palette().danger, 0.2).into()),
+            border: border::rounded(8.0),
+            ..container::Style::default()
+        })
+}
+
+pub fn elapsed_text<'a>(elapsed: Duration) -> Text<'a> {
+    text(t_fmt("elapsed", &[("time", &utils::format_hms(elapsed))]))
+        .size(12)
+        .align_x(Horizontal::Center)
+        .style(|theme: &Theme| text::Style {
+            color: Some(custom_theme::faded_text(theme, 0.7)),
+        })
+}
+
+/// Newest lap first, each with its split and the time since the lap before
+pub fn laps_list<'a>(laps: &[Duration]) -> Container<'a, Msg> {
+    let rows = laps
+        .iter()
+        .enumerate()
+        .rev()
+        .fold(column![].spacing(4), |rows, (index, split)| {
+            let previous = index.checked_sub(1).map_or(Duration::ZERO, |i| laps[i]);
+            rows.push(
+                row![
+                    text(t_fmt("lap_number", &[("number", &(index + 1).to_string())])).size(12),
+                    horizontal_space(),
+                    text(utils::format_hms(*split)).size(12),
+                    text(format!(
+                        "+{}",
+                        utils::format_hms(split.saturating_sub(previous))
+                    ))
+                    .size(12)
+                    .style(|theme: &Theme| text::Style {
+                        color: Some(custom_theme::faded_text(theme, 0.7)),
+                    }),
+                ]
+                .spacing(10),
+            )
+        });
+
+    container(scrollable(container(rows).padding([0, 10])).width(250)).max_height(120)
+}
+
+pub fn ends_at_text<'a>(ends_at: &str) -> Text<'a> {
+    text(t_fmt("ends_at", &[("time", ends_at)]))
+        .size(12)
+        .align_x(Horizontal::Center)
+        .style(|theme: &Theme| text::Style {
+            color: Some(custom_theme::faded_text(theme, 0.7)),
+        })
+}
+
+pub fn created_text<'a>(created_at: SystemTime) -> Text<'a> {
+    let ago = utils::format_ago(created_at, SystemTime::now());
+    text(t_fmt("created", &[("time", &ago)]))
+        .size(12)
+        .align_x(Horizontal::Center)
+        .style(|theme: &Theme| text::Style {
+            color: Some(custom_theme::faded_text(theme, 0.7)),
+        })
+}
+
+pub fn error_text<'a>(message: &str) -> Text<'a> {
+    text(message.to_string())
+        .size(12)
+        .align_x(Horizontal::Center)
+        .style(|theme: &Theme| text::Style {
+            color: Some(theme.palette().danger),
+        })
+}
+
+pub fn warning_banner<'a>(
+    message: &str,
+    action: Option<(&'static str, Msg)>,
+    on_dismiss: Msg,
+) -> Container<'a, Msg> {
     container(
-        custom_button(plus_icon(), CustomButtonType::Primary, None, None).on_press(Msg::AddTimer),
+        row![text(message.to_string()).size(12).width(Length::Fill)]
+            .push_maybe(action.map(|(label, on_press)| {
+                custom_button(
+                    text(label).size(12),
+                    CustomButtonType::Primary,
+                    Some(60f32),
+                    Some(30f32),
+                )
+                .on_press(on_press)
+            }))
+            .push(
+                custom_button(
+                    text(t("dismiss")).size(12),
+                    CustomButtonType::Secondary,
+                    Some(60f32),
+                    Some(30f32),
+                )
+                .on_press(on_dismiss),
+            )
+            .spacing(10)
+            .align_y(Alignment::Center),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .style(|theme: &Theme| container::Style {
+        text_color: None,
+        background: Some(custom_theme::faded(theme, theme.palette().danger, 0.2).into()),
+        border: Border::default().rounded(8),
+        shadow: Shadow::default(),
+    })
+}
+
+/// Short message at the bottom of the window that goes away on its own, with
+/// the number of messages waiting behind it
+pub fn toast<'a>(message: &str, queued: usize) -> Container<'a, Msg> {
+    let label = if queued == 0 {
+        message.to_string()
+    } else {
+        format!("{message} (+{queued})")
+    };
+    container(
+        container(text(label).size(12))
+            .padding([8, 16])
+            .style(|theme: &Theme| container::Style {
+                text_color: Some(theme.palette().background),
+                background: Some(theme.palette().text.into()),
+                border: Border::default().rounded(8),
+                shadow: Shadow::default(),
+            }),
+    )
+    .padding(20)
+    .center_x(Length::Fill)
+    .align_bottom(Length::Fill)
+}
+
+/// Row whose children run right to left in RTL layouts
+pub fn directed_row<'a>(mut children: Vec<Element<'a, Msg>>) -> Row<'a, Msg> {
+    if is_rtl() {
+        children.reverse();
+    }
+    Row::with_children(children)
+}
+
+/// Side a line of content starts from, left unless the layout is RTL
+pub fn leading() -> Horizontal {
+    if is_rtl() {
+        Horizontal::Right
+    } else {
+        Horizontal::Left
+    }
+}
+
+pub fn trailing() -> Horizontal {
+    if is_rtl() {
+        Horizontal::Left
+    } else {
+        Horizontal::Right
+    }
+}
+
+pub fn top_bar<'a>(sort: SortOrder, profiles: &[String], profile: &str) -> Container<'a, Msg> {
+    container(
+        directed_row(vec![
+            pick_list(
+                profiles.to_vec(),
+                Some(profile.to_string()),
+                Msg::ProfileSelected,
+            )
+            .text_size(12)
+            .into(),
+            pick_list(SortOrder::ALL, Some(sort), Msg::SortSelected)
+                .text_size(12)
+                .into(),
+            custom_button(
+                text(t("stats")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                None,
+            )
+            .on_press(Msg::Navigate(Page::Dashboard))
+            .into(),
+            custom_button(
+                text(t("templates")).size(12),
+                CustomButtonType::Secondary,
+                Some(80f32),
+                None,
+            )
+            .on_press(Msg::Navigate(Page::Templates))
+            .into(),
+            custom_button(
+                text(t("settings")).size(12),
+                CustomButtonType::Secondary,
+                Some(70f32),
+                None,
+            )
+            .on_press(Msg::Navigate(Page::Settings))
+            .into(),
+            custom_button(
+                text(t("paste")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                None,
+            )
+            .on_press(Msg::PasteTimer)
+            .into(),
+            custom_button(
+                text(t("select")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                None,
+            )
+            .on_press(Msg::ToggleSelecting)
+            .into(),
+            custom_button(
+                text(t("mini")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                None,
+            )
+            .on_press(Msg::ToggleMiniMode)
+            .into(),
+            labelled(
+                custom_button(plus_icon(), CustomButtonType::Primary, None, None)
+                    .on_press(Msg::AddTimer),
+                t("add_timer_shortcut"),
+            )
+            .into(),
+        ])
+        .spacing(20)
+        .align_y(Alignment::Center),
     )
     .padding(10)
     .width(Length::Fill)
     .align_y(Alignment::Start)
-    .align_x(Alignment::End)
+    .align_x(trailing())
+}
+
+pub fn quick_add_bar<'a>(input: &str, error: Option<&str>) -> Container<'a, Msg> {
+    container(
+        column![text_input(t("quick_add_placeholder"), input)
+            .size(12)
+            .padding(8)
+            .on_input(Msg::QuickAddInput)
+            .on_submit(Msg::QuickAdd(input.to_string()))]
+        .push_maybe(error.map(error_text))
+        .spacing(4),
+    )
+    .padding([0, 10])
+    .width(Length::Fill)
+}
+
+/// One strip with the name and time left of every running timer, pressing one
+/// scrolls to its card. `running` holds id, name and remaining time.
+pub fn running_bar<'a>(running: Vec<(Uuid, String, String)>) -> Scrollable<'a, Msg> {
+    let entries = running
+        .into_iter()
+        .fold(row![].spacing(6), |entries, (id, name, remaining)| {
+            entries.push(
+                custom_button(
+                    row![text(name).size(12), text(remaining).size(12)].spacing(6),
+                    CustomButtonType::Secondary,
+                    None,
+                    Some(24f32),
+                )
+                .on_press(Msg::ScrollTo(id)),
+            )
+        });
+
+    scrollable(container(entries).padding([0, 10]))
+        .direction(scrollable::Direction::Horizontal(
+            scrollable::Scrollbar::new().width(4).scroller_width(4),
+        ))
+        .width(Length::Fill)
+}
+
+/// Takes the place of the quick-add bar while cards are being selected
+pub fn selection_bar<'a>(count: usize) -> Container<'a, Msg> {
+    let any = count > 0;
+    container(
+        row![
+            text(t_fmt("selected", &[("count", &count.to_string())]))
+                .size(12)
+                .width(Length::Fill),
+            custom_button(
+                text(t("start")).size(12),
+                CustomButtonType::Primary,
+                None,
+                Some(30f32),
+            )
+            .on_press_maybe(any.then_some(Msg::StartSelected)),
+            custom_button(
+                text(t("stop")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                Some(30f32),
+            )
+            .on_press_maybe(any.then_some(Msg::StopSelected)),
+            custom_button(
+                text(t("delete")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                Some(30f32),
+            )
+            .on_press_maybe(any.then_some(Msg::DeleteSelected)),
+            custom_button(
+                text(t("done_selecting")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                Some(30f32),
+            )
+            .on_press(Msg::ToggleSelecting),
+        ]
+        .spacing(10)
+        .align_y(Alignment::Center),
+    )
+    .padding([0, 10])
+    .width(Length::Fill)
+}
+
+pub fn mini_page<'a>(running: Vec<(String, String)>) -> Container<'a, Msg> {
+    let timers = if running.is_empty() {
+        column![text(t("no_running_timers")).size(12)]
+    } else {
+        running
+            .into_iter()
+            .fold(column![].spacing(6), |timers, (name, remaining)| {
+                timers.push(
+                    row![
+                        text(name).size(12),
+                        horizontal_space(),
+                        text(remaining).size(16),
+                    ]
+                    .align_y(Alignment::Center),
+                )
+            })
+    };
+
+    container(
+        column![
+            timers,
+            custom_button(
+                text(t("expand")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                None
+            )
+            .on_press(Msg::ToggleMiniMode),
+        ]
+        .spacing(10)
+        .align_x(Alignment::Center),
+    )
+    .padding(10)
+    .width(Length::Fill)
+    .height(Length::Fill)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn settings_page<'a>(
+    config: &Config,
+    accent_input: &str,
+    sound_error: Option<&str>,
+    import_path: &str,
+    import_result: Option<&str>,
+    export_path: &str,
+    export_result: Option<&str>,
+    new_profile: &str,
+    new_view_preset: &str,
+    capturing: Option<Action>,
+    shortcut_error: Option<&str>,
+) -> Container<'a, Msg> {
+    let swatches = ACCENT_SWATCHES
+        .iter()
+        .fold(row![].spacing(6), |swatches, hex| {
+            swatches.push(accent_swatch(hex))
+        });
+
+    container(
+        column![
+            row![
+                custom_button(
+                    text(t("back")).size(12),
+                    CustomButtonType::Secondary,
+                    None,
+                    None
+                )
+                .on_press(Msg::Navigate(Page::Timers)),
+                text(t("settings")).size(20),
+            ]
+            .spacing(20)
+            .align_y(Alignment::Center),
+            setting_row(
+                t("view_preset"),
+                row![
+                    pick_list(
+                        config.view_presets.clone(),
+                        config.active_view_preset().cloned(),
+                        Msg::ViewPresetSelected
+                    )
+                    .placeholder(t("custom"))
+                    .text_size(12),
+                    text_input(t("preset_name"), new_view_preset)
+                        .width(150)
+                        .size(12)
+                        .on_input(Msg::ViewPresetNameInput)
+                        .on_submit(Msg::SaveViewPreset),
+                    custom_button(
+                        text(t("save")).size(12),
+                        CustomButtonType::Secondary,
+                        None,
+                        Some(30f32)
+                    )
+                    .on_press_maybe(
+                        (!new_view_preset.trim().is_empty()).then_some(Msg::SaveViewPreset)
+                    ),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("theme"),
+                pick_list(
+                    &ThemeChoice::ALL[..],
+                    Some(config.theme),
+                    Msg::ThemeSelected
+                )
+                .text_size(12)
+            ),
+            setting_row(
+                t("accent"),
+                row![
+                    swatches,
+                    text_input("#5294e2", accent_input)
+                        .width(90)
+                        .size(12)
+                        .on_input(Msg::AccentInput),
+                    custom_button(
+                        text(t("default")).size(12),
+                        CustomButtonType::Secondary,
+                        Some(60f32),
+                        Some(30f32)
+                    )
+                    .on_press(Msg::AccentInput(String::new())),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("digit_entry"),
+                toggler(config.digit_entry).on_toggle(Msg::DigitEntry)
+            ),
+            setting_row(
+                t("touch_mode"),
+                toggler(config.touch_mode).on_toggle(Msg::TouchMode)
+            ),
+            setting_row(
+                t("reduce_motion"),
+                toggler(config.reduce_motion).on_toggle(Msg::ReduceMotion)
+            ),
+            setting_row(
+                t("percentage"),
+                row![toggler(config.show_percentage).on_toggle(Msg::ShowPercentage)]
+                    .push_maybe(config.show_percentage.then(|| {
+                        pick_list(Percentage::ALL, Some(config.percentage), Msg::Percentage)
+                            .text_size(12)
+                    }))
+                    .spacing(10)
+                    .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("round_to"),
+                row![toggler(config.round_to.is_some()).on_toggle(|enabled| {
+                    Msg::RoundTo(enabled.then_some(Duration::from_secs(5 * 60)))
+                })]
+                .push_maybe(config.round_to.map(|step| {
+                    pick_list(ROUND_MINUTES, Some(step.as_secs() / 60), |minutes| {
+                        Msg::RoundTo(Some(Duration::from_secs(minutes * 60)))
+                    })
+                    .text_size(12)
+                }))
+                .push_maybe(config.round_to.map(|_| text(t("minutes_short")).size(12)))
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("voice_countdown"),
+                toggler(config.announce).on_toggle(Msg::Announce)
+            ),
+            setting_row(
+                t("show_elapsed"),
+                toggler(config.show_elapsed).on_toggle(Msg::ShowElapsed)
+            ),
+            setting_row(
+                t("show_ends_at"),
+                toggler(config.show_ends_at).on_toggle(Msg::ShowEndsAt)
+            ),
+            setting_row(
+                t("clock_24h"),
+                toggler(config.clock_24h).on_toggle(Msg::Clock24h)
+            ),
+            setting_row(
+                t("show_created"),
+                toggler(config.show_created).on_toggle(Msg::ShowCreated)
+            ),
+            setting_row(
+                t("number_duplicates"),
+                toggler(config.number_duplicates).on_toggle(Msg::NumberDuplicates)
+            ),
+            setting_row(
+                t("notification"),
+                row![
+                    pick_list(
+                        Urgency::ALL,
+                        Some(config.notification_urgency),
+                        Msg::NotificationUrgency
+                    )
+                    .text_size(12),
+                    pick_list(
+                        NotificationTimeout::ALL,
+                        Some(config.notification_timeout),
+                        Msg::NotificationTimeout
+                    )
+                    .text_size(12),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("final_seconds"),
+                row![
+                    toggler(config.final_warning.enabled).on_toggle(Msg::FinalWarning),
+                    pick_list(
+                        WARNING_SECONDS,
+                        Some(config.final_warning.seconds),
+                        Msg::FinalWarningSeconds
+                    )
+                    .text_size(12),
+                    toggler(config.final_warning.pulse)
+                        .label(t("pulse"))
+                        .text_size(12)
+                        .on_toggle(Msg::FinalWarningPulse),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("alarm_plays"),
+                pick_list(
+                    ALARM_REPEATS,
+                    Some(config.alarm_repeats),
+                    Msg::DefaultAlarmRepeats
+                )
+                .text_size(12)
+            ),
+            setting_row(
+                t("ring_until_stopped"),
+                toggler(config.ring_until_dismissed).on_toggle(Msg::RingUntilDismissed)
+            ),
+            setting_row(
+                t("hold_finished"),
+                toggler(config.hold_finished).on_toggle(Msg::HoldFinished)
+            ),
+            setting_row(
+                t("after_finish"),
+                pick_list(
+                    AfterFinish::ALL,
+                    Some(config.after_finish),
+                    Msg::AfterFinish
+                )
+                .text_size(12)
+            ),
+            setting_row(
+                t("flash_taskbar"),
+                toggler(config.request_attention).on_toggle(Msg::RequestAttention)
+            ),
+            setting_row(
+                t("pause_when_away"),
+                toggler(config.pause_when_away).on_toggle(Msg::PauseWhenAway)
+            ),
+            setting_row(
+                t("daily_goal"),
+                row![
+                    slider(0..=20, config.daily_goal, Msg::DailyGoalChanged).width(120),
+                    text(if config.daily_goal == 0 {
+                        t("off").to_string()
+                    } else {
+                        t_fmt(
+                            "daily_goal_count",
+                            &[("count", &config.daily_goal.to_string())],
+                        )
+                    })
+                    .size(12),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("sound"),
+                text_input(
+                    t("bundled_alarm"),
+                    &config
+                        .sound_path
+                        .as_ref()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default()
+                )
+                .width(250)
+                .size(12)
+                .on_input(Msg::SoundPathChanged)
+                .on_submit(Msg::CheckSoundPath)
+            ),
+            setting_row(
+                t("start_sound"),
+                text_input(
+                    t("none"),
+                    &config
+                        .start_sound
+                        .as_ref()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default()
+                )
+                .width(250)
+                .size(12)
+                .on_input(Msg::StartSoundPathChanged)
+            ),
+            setting_row(
+                t("tick_sound"),
+                text_input(
+                    t("none"),
+                    &config
+                        .tick_sound
+                        .as_ref()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default()
+                )
+                .width(250)
+                .size(12)
+                .on_input(Msg::TickSoundPathChanged)
+            ),
+            setting_row(
+                t("volume"),
+                row![
+                    slider(0.0..=1.0, config.volume, Msg::VolumeChanged)
+                        .step(0.05)
+                        .width(120),
+                    custom_button(
+                        text(t("play_test_sound")).size(12),
+                        CustomButtonType::Secondary,
+                        Some(110f32),
+                        Some(30f32)
+                    )
+                    .on_press(Msg::TestSound),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+            setting_row(
+                t("ui_scale"),
+                row![
+                    slider(
+                        Config::SCALE_FACTOR_RANGE,
+                        config.scale_factor(),
+                        Msg::ScaleFactorChanged
+                    )
+                    .step(0.05)
+                    .width(120),
+                    text(format!("{:.0}%", config.scale_factor() * 100.0)).size(12),
+                ]
+                .spacing(10)
+                .align_y(Alignment::Center)
+            ),
+        ]
+        .push_maybe(sound_error.map(error_text))
+        .push(setting_row(
+            t("new_profile"),
+            row![
+                text_input(t("profile_name"), new_profile)
+                    .width(250)
+                    .size(12)
+                    .on_input(Msg::NewProfileInput)
+                    .on_submit(Msg::AddProfile),
+                custom_button(
+                    text(t("create")).size(12),
+                    CustomButtonType::Secondary,
+                    None,
+                    Some(30f32)
+                )
+                .on_press(Msg::AddProfile),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        ))
+        .push(setting_row(
+            t("import_csv"),
+            row![
+                text_input("/path/to/timers.csv", import_path)
+                    .width(250)
+                    .size(12)
+                    .on_input(Msg::ImportPathChanged)
+                    .on_submit(Msg::ImportCsv(import_path.into())),
+                custom_button(
+                    text(t("import")).size(12),
+                    CustomButtonType::Secondary,
+                    None,
+                    Some(30f32)
+                )
+                .on_press(Msg::ImportCsv(import_path.into())),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        ))
+        .push_maybe(import_result.map(|result| text(result.to_string()).size(12)))
+        .push(setting_row(
+            t("export_history"),
+            row![
+                text_input("/path/to/history.csv", export_path)
+                    .width(250)
+                    .size(12)
+                    .on_input(Msg::ExportPathChanged)
+                    .on_submit(Msg::ExportCsv(export_path.into())),
+                custom_button(
+                    text(t("export")).size(12),
+                    CustomButtonType::Secondary,
+                    None,
+                    Some(30f32)
+                )
+                .on_press(Msg::ExportCsv(export_path.into())),
+            ]
+            .spacing(10)
+            .align_y(Alignment::Center),
+        ))
+        .push_maybe(export_result.map(|result| text(result.to_string()).size(12)))
+        .push(shortcuts_section(config, capturing, shortcut_error))
+        .spacing(15),
+    )
+    .padding(20)
+    .width(Length::Fill)
+}
+
+pub fn action_label(action: Action) -> &'static str {
+    t(match action {
+        Action::OpenPalette => "open_palette",
+        Action::AddTimer => "add_timer",
+        Action::UndoDelete => "undo_delete",
+        Action::DeleteFocused => "delete_focused",
+    })
+}
+
+/// Current shortcut of every action with a button to rebind it, pressing it
+/// waits for the next key combination
+fn shortcuts_section<'a>(
+    config: &Config,
+    capturing: Option<Action>,
+    error: Option<&str>,
+) -> Column<'a, Msg> {
+    config
+        .shortcuts()
+        .into_iter()
+        .fold(
+            column![text(t("shortcuts")).size(16)].spacing(10),
+            |section, (action, shortcuts)| {
+                let waiting = capturing == Some(action);
+                section.push(setting_row(
+                    action_label(action),
+                    row![
+                        text(if waiting {
+                            t("press_shortcut").to_string()
+                        } else {
+                            shortcuts.join(", ")
+                        })
+                        .size(12)
+                        .width(150),
+                        custom_button(
+                            text(t("change")).size(12),
+                            CustomButtonType::Secondary,
+                            None,
+                            Some(30f32)
+                        )
+                        .on_press_maybe((!waiting).then_some(Msg::CaptureShortcut(action))),
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                ))
+            },
+        )
+        .push_maybe(error.map(error_text))
+        .push(
+            custom_button(
+                text(t("reset_shortcuts")).size(12),
+                CustomButtonType::Secondary,
+                None,
+                Some(30f32),
+            )
+            .on_press(Msg::ResetShortcuts),
+        )
+}
+
+fn setting_row<'a>(label: &'a str, control: impl Into<Element<'a, Msg>>) -> Row<'a, Msg> {
+    row![text(label).size(14).width(100), control.into()]
+        .spacing(10)
+        .align_y(Alignment::Center)
+}
+
+pub fn dashboard_page<'a>(summary: Option<&stats::Summary>, goal: u32) -> Container<'a, Msg> {
+    let header = row![
+        custom_button(
+            text(t("back")).size(12),
+            CustomButtonType::Secondary,
+            None,
+            None
+        )
+        .on_press(Msg::Navigate(Page::Timers)),
+        text(t("focus_time")).size(20),
+    ]
+    .spacing(20)
+    .align_y(Alignment::Center);
+
+    let content = match summary.filter(|summary| !summary.by_name.is_empty()) {
+        None => column![text(t("no_stats")).size(14)],
+        Some(summary) => {
+            let longest = summary.by_name[0].1.as_secs().max(1);
+            let bars = summary
+                .by_name
+                .iter()
+                .fold(column![].spacing(8), |bars, (name, total)| {
+                    bars.push(stat_bar(name, *total, longest))
+                });
+
+            column![setting_row(
+                t("today"),
+                text(utils::format_hms(summary.today)).size(14)
+            ),]
+            .push_maybe((goal > 0).then(|| goal_row(summary.finished_today, goal)))
+            .push(setting_row(
+                t("this_week"),
+                text(utils::format_hms(summary.week)).size(14),
+            ))
+            .push(text(t("by_timer")).size(16))
+            .push(bars)
+            .spacing(15)
+        }
+    };
+
+    container(column![header, content].spacing(15))
+        .padding(20)
+        .width(Length::Fill)
+}
+
+fn goal_row<'a>(finished: u32, goal: u32) -> Row<'a, Msg> {
+    let progress = text(t_fmt(
+        "goal_progress",
+        &[
+            ("finished", &finished.to_string()),
+            ("goal", &goal.to_string()),
+        ],
+    ))
+    .size(14);
+    let progress = if finished >= goal {
+        row![
+            progress,
+            text(t("goal_reached"))
+                .size(14)
+                .style(|theme: &Theme| text::Style {
+                    color: Some(theme.palette().success),
+                }),
+        ]
+        .spacing(10)
+    } else {
+        row![progress]
+    };
+    setting_row(t("goal"), progress)
+}
+
+pub fn templates_page<'a>(
+    templates: &[Template],
+    duration_inputs: &HashMap<Uuid, String>,
+) -> Container<'a, Msg> {
+    let header = row![
+        custom_button(
+            text(t("back")).size(12),
+            CustomButtonType::Secondary,
+            None,
+            None
+        )
+        .on_press(Msg::Navigate(Page::Timers)),
+        text(t("templates")).size(20),
+        horizontal_space(),
+        labelled(
+            custom_button(plus_icon(), CustomButtonType::Primary, None, None)
+                .on_press(Msg::AddTemplate),
+            t("add_template")
+        ),
+    ]
+    .spacing(20)
+    .align_y(Alignment::Center);
+
+    let content = if templates.is_empty() {
+        column![text(t("no_templates")).size(14)]
+    } else {
+        templates
+            .iter()
+            .fold(column![].spacing(15), |content, template| {
+                content.push(template_card(template, duration_inputs.get(&template.id)))
+            })
+    };
+
+    container(column![header, content].spacing(15))
+        .padding(20)
+        .width(Length::Fill)
+}
+
+fn template_card<'a>(template: &Template, duration_input: Option<&String>) -> Column<'a, Msg> {
+    let id = template.id;
+    let duration = duration_input
+        .cloned()
+        .unwrap_or_else(|| utils::format_hms(template.duration));
+    let invalid = utils::parse_duration(&duration).is_none();
+
+    column![
+        setting_row(
+            t("name"),
+            text_input(t("name"), &template.name)
+                .size(12)
+                .padding(8)
+                .width(250)
+                .on_input(move |name| Msg::TemplateName((id, name)))
+        ),
+        setting_row(
+            t("duration"),
+            text_input(t("duration_placeholder"), &duration)
+                .size(12)
+                .padding(8)
+                .width(250)
+                .on_input(move |duration| Msg::TemplateDuration((id, duration)))
+        ),
+    ]
+    .push_maybe(invalid.then(|| error_text(t("unrecognized_duration"))))
+    .push(
+        row![
+            toggler(template.notify)
+                .label(t("notification"))
+                .text_size(12)
+                .on_toggle(move |notify| Msg::TemplateNotify((id, notify))),
+            toggler(template.silent)
+                .label(t("silent"))
+                .text_size(12)
+                .on_toggle(move |silent| Msg::TemplateSilent((id, silent))),
+            horizontal_space(),
+            custom_button(
+                text(t("use_template")).size(12),
+                CustomButtonType::Primary,
+                None,
+                None
+            )
+            .on_press(Msg::UseTemplate(id)),
+            labelled(
+                custom_button(
+                    delete_icon().size(14f32),
+                    CustomButtonType::Secondary,
+                    None,
+                    None
+                )
+                .on_press(Msg::DeleteTemplate(id)),
+                t("delete_template")
+            ),
+        ]
+        .spacing(20)
+        .align_y(Alignment::Center),
+    )
+    .spacing(8)
+}
+
+fn stat_bar<'a>(name: &str, total: std::time::Duration, longest: u64) -> Row<'a, Msg> {
+    // FillPortion needs integers, so scale the bar against the longest total
+    let filled = (total.as_secs() * 100 / longest).max(1) as u16;
+    row![
+        text(name.to_string()).size(12).width(100),
+        row![container(text(utils::format_hms(total)).size(12))
+            .padding([2, 6])
+            .width(Length::FillPortion(filled))
+            .style(|theme: &Theme| container::Style {
+                text_color: None,
+                background: Some(theme.palette().primary.scale_alpha(0.6).into()),
+                border: Border::default().rounded(4),
+                shadow: Shadow::default(),
+            }),]
+        .push_maybe(
+            (filled < 100).then(|| horizontal_space().width(Length::FillPortion(100 - filled)))
+        )
+        .width(Length::Fill),
+    ]
+    .spacing(10)
+    .align_y(Alignment::Center)
+}
+
+const ACCENT_SWATCHES: [&str; 6] = [
+    "#5294e2", "#2eb398", "#9b59b6", "#e5a50a", "#e66100", "#dc322f",
+];
+
+fn accent_swatch<'a>(hex: &'static str) -> Button<'a, Msg> {
+    let color = custom_theme::parse_hex(hex).unwrap_or_default();
+    button("")
+        .width(20)
+        .height(20)
+        .style(move |_: &Theme, _| button::Style {
+            background: Some(color.into()),
+            border: border::rounded(10.0),
+            ..button::Style::default()
+        })
+        .on_press(Msg::AccentInput(hex.to_string()))
+}
+
+pub fn palette_input_id() -> text_input::Id {
+    text_input::Id::new("command-palette")
+}
+
+/// Overlay listing `commands`, Enter runs the first one and Escape closes it
+pub fn command_palette<'a>(query: &str, commands: Vec<(String, Command)>) -> Container<'a, Msg> {
+    let first = commands.first().map(|(_, command)| command.clone());
+    let input = text_input(t("palette_placeholder"), query)
+        .id(palette_input_id())
+        .size(14)
+        .padding(10)
+        .on_input(Msg::PaletteQuery);
+    let input = match first {
+        Some(command) => input.on_submit(Msg::RunCommand(command)),
+        None => input,
+    };
+
+    let entries = commands
+        .into_iter()
+        .fold(column![].spacing(4), |entries, (label, command)| {
+            entries.push(
+                button(text(label).size(12))
+                    .width(Length::Fill)
+                    .style(button::text)
+                    .on_press(Msg::RunCommand(command)),
+            )
+        });
+
+    container(
+        container(column![input, scrollable(entries).height(Length::Shrink)].spacing(10))
+            .width(360)
+            .max_height(320)
+            .padding(10)
+            .style(|theme: &Theme| container::Style {
+                text_color: None,
+                background: Some(theme.palette().background.into()),
+                border: Border::default()
+                    .rounded(8)
+                    .width(1)
+                    .color(theme.palette().primary),
+                shadow: Shadow::default(),
+            }),
+    )
+    .padding(40)
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .align_x(Horizontal::Center)
+}
+
+/// Asks what to do with timers that were running when the app last closed,
+/// everything behind it is blocked until one is picked
+pub fn resume_prompt<'a>(count: usize) -> Container<'a, Msg> {
+    let message = if count == 1 {
+        t("resume_one").to_string()
+    } else {
+        t_fmt("resume_many", &[("count", &count.to_string())])
+    };
+
+    container(opaque(
+        container(
+            column![
+                text(message).size(14),
+                row![
+                    custom_button(
+                        text(t("discard")).size(12),
+                        CustomButtonType::Secondary,
+                        Some(80f32),
+                        Some(30f32),
+                    )
+                    .on_press(Msg::DiscardSession),
+                    custom_button(
+                        text(t("resume")).size(12),
+                        CustomButtonType::Primary,
+                        Some(80f32),
+                        Some(30f32),
+                    )
+                    .on_press(Msg::ResumeSession),
+                ]
+                .spacing(10),
+            ]
+            .spacing(20)
+            .align_x(Alignment::Center),
+        )
+        .width(320)
+        .padding(20)
+        .style(|theme: &Theme| container::Style {
+            text_color: None,
+            background: Some(theme.palette().background.into()),
+            border: Border::default()
+                .rounded(8)
+                .width(1)
+                .color(theme.palette().primary),
+            shadow: Shadow::default(),
+        }),
+    ))
+    .center(Length::Fill)
+    .style(|theme: &Theme| container::Style {
+        background: Some(custom_theme::faded(theme, Color::BLACK, 0.4).into()),
+        ..container::Style::default()
+    })
+}
+
+pub fn timers_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("timers")
 }
 
 pub fn scrollable_content<'a>(content: impl Into<Element<'a, Msg>>) -> Scrollable<'a, Msg> {
@@ -257,6 +1809,20 @@ pub fn delete_icon<'a>() -> Text<'a> {
 pub fn save_icon<'a>() -> Text<'a> {
     icon('\u{e805}')
 }
+pub fn pin_icon<'a>(pinned: bool) -> Text<'a> {
+    // Also from the emoji font, faded out while the timer isn't pinned
+    text('\u{1f4cc}')
+        .size(14)
+        .style(move |theme: &Theme| text::Style {
+            color: (!pinned).then(|| custom_theme::faded_text(theme, 0.4)),
+        })
+}
+
+pub fn muted_icon<'a>() -> Text<'a> {
+    // Not part of the icon font, rendered through the system emoji font instead
+    text('\u{1f515}').size(14)
+}
+
 fn icon<'a>(codepoint: char) -> Text<'a> {
     const ICON_FONT: Font = Font::with_name("icons-font");
     text(codepoint).font(ICON_FONT)
@@ -0,0 +1,628 @@
+use iced::keyboard;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    i18n::{t, t_fmt},
+    timer::Timer,
+};
+
+/// User preferences, kept in `config.toml` apart from the timers in `state.json`.
+/// Missing fields (or a missing file) fall back to the defaults below.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Language code like "de" for UI strings, follows `LANG` when unset
+    pub locale: Option<String>,
+    /// Forces the right-to-left layout on or off, follows the language when unset
+    pub rtl: Option<bool>,
+    pub theme: ThemeChoice,
+    /// Hex color replacing the theme's primary color, e.g. "#5294e2"
+    pub accent: Option<String>,
+    pub show_percentage: bool,
+    /// Whether the percentage counts what's left or what's done
+    pub percentage: Percentage,
+    /// Time spent so far, in small print under the countdown of running timers
+    pub show_elapsed: bool,
+    /// "created 2 days ago" on stopped timers
+    pub show_created: bool,
+    /// "ends at 15:42" under running countdowns
+    pub show_ends_at: bool,
+    /// 24-hour clock times, "3:42 PM" style when off
+    pub clock_24h: bool,
+    /// Numbers a timer named like another one when it's saved or started,
+    /// rather than only warning about it
+    pub number_duplicates: bool,
+    /// Alarm sound, the bundled one is used when unset
+    pub sound_path: Option<PathBuf>,
+    /// Played when a timer starts, nothing plays when unset
+    pub start_sound: Option<PathBuf>,
+    /// Played every second of the final warning seconds, nothing plays when unset
+    pub tick_sound: Option<PathBuf>,
+    pub volume: f32,
+    /// Seed new timers with the duration of the most recently started one
+    pub remember_last_duration: bool,
+    /// Duration of the timer created when there are none, like on a fresh install.
+    /// Wins over `last_duration` there, new timers added later still use that.
+    pub default_duration: Option<Duration>,
+    pub last_duration: Duration,
+    /// Multiplier on top of the scaling reported by the OS, 1.0 keeps the
+    /// system scaling as is
+    pub scale_factor: f64,
+    /// Display order of the timer cards, `state.json` keeps creation order
+    pub sort: SortOrder,
+    /// Small always-on-top window with only the running timers
+    pub mini_mode: bool,
+    /// How often running timers wake up, the remaining time is measured from
+    /// the clock so a coarser interval only makes the display update less often
+    pub tick_interval: Duration,
+    /// Number of timers to finish each day, 0 turns the goal off
+    pub daily_goal: u32,
+    /// Keep ringing until the alarm is stopped instead of playing the sound once
+    pub ring_until_dismissed: bool,
+    /// Keep finished timers marked done until dismissed, instead of stopping
+    /// them once the alarm has played
+    pub hold_finished: bool,
+    /// What becomes of a countdown once it's done and dismissed
+    pub after_finish: AfterFinish,
+    /// Flash the taskbar entry when an alarm goes off, until the window is focused
+    /// or a ringing alarm is stopped. Where the window manager can't flash it only
+    /// marks the window urgent, and nothing happens where it ignores that too.
+    pub request_attention: bool,
+    /// Pause running timers while the session is locked or idle and resume them
+    /// once it's back. Needs systemd-logind, see `utils::session_away`.
+    pub pause_when_away: bool,
+    /// Desktops may ignore it, critical ones usually stay on top of fullscreen windows
+    pub notification_urgency: Urgency,
+    /// How long the notification stays up, `Sticky` until it's dismissed
+    pub notification_timeout: NotificationTimeout,
+    /// Plays of the alarm sound for timers without their own count
+    pub alarm_repeats: u32,
+    /// Longest duration a timer accepts, raise it for multi-day timers
+    pub max_duration: Duration,
+    /// Step new runs are rounded to when started, e.g. 5 minutes
+    pub round_to: Option<Duration>,
+    pub final_warning: FinalWarning,
+    /// Speak the time left at `announce_at`, for hands-free use
+    pub announce: bool,
+    /// Remaining times to announce for timers without their own list
+    pub announce_at: Vec<Duration>,
+    /// On-screen keypad under stopped timers, for devices without a keyboard
+    pub touch_mode: bool,
+    /// Cards that went off stay highlighted instead of pulsing
+    pub reduce_motion: bool,
+    /// One field for the whole duration that fills from the right as digits are
+    /// typed, instead of separate hours, minutes and seconds
+    pub digit_entry: bool,
+    /// Duration of one full turn of the dial around stopped timers
+    pub dial_turn: Duration,
+    /// Broker that gets timer events, needs the `mqtt` feature
+    pub mqtt: Option<Mqtt>,
+    /// Key combinations like "Ctrl+N" per action, the defaults apply to actions
+    /// left out and to ones clashing with an action listed before them
+    pub shortcuts: HashMap<Action, Vec<String>>,
+    /// Saved looks to switch between, like a dense dark one for work and a large
+    /// light one for the kitchen
+    pub view_presets: Vec<ViewPreset>,
+    /// Timer set in use, see `utils::state_path`. Settings are shared by all profiles.
+    pub profile: String,
+    /// Directory for timers, templates and history instead of the local state
+    /// directory, `OXYCLOCK_STATE_DIR` takes precedence
+    pub state_dir: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            locale: None,
+            rtl: None,
+            theme: ThemeChoice::System,
+            accent: None,
+            show_percentage: false,
+            percentage: Percentage::Remaining,
+            show_elapsed: false,
+            show_created: false,
+            show_ends_at: false,
+            clock_24h: true,
+            number_duplicates: false,
+            sound_path: None,
+            start_sound: None,
+            tick_sound: None,
+            volume: 1.0,
+            remember_last_duration: true,
+            default_duration: None,
+            last_duration: Duration::from_secs(0),
+            scale_factor: 1.0,
+            sort: SortOrder::Created,
+            mini_mode: false,
+            tick_interval: Duration::from_secs(1),
+            daily_goal: 0,
+            ring_until_dismissed: false,
+            hold_finished: true,
+            after_finish: AfterFinish::Keep,
+            request_attention: false,
+            pause_when_away: false,
+            notification_urgency: Urgency::Normal,
+            notification_timeout: NotificationTimeout::Auto,
+            alarm_repeats: 1,
+            max_duration: Duration::from_secs(24 * 3600),
+            round_to: None,
+            final_warning: FinalWarning::default(),
+            announce: false,
+            announce_at: vec![
+                Duration::from_secs(5 * 60),
+                Duration::from_secs(60),
+                Duration::from_secs(10),
+            ],
+            touch_mode: false,
+            reduce_motion: false,
+            digit_entry: false,
+            dial_turn: Duration::from_secs(3600),
+            mqtt: None,
+            shortcuts: HashMap::new(),
+            view_presets: Vec::new(),
+            profile: crate::utils::DEFAULT_PROFILE.to_string(),
+            state_dir: None,
+        }
+    }
+}
+
+/// Turns the running time red once fewer than `seconds` are left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FinalWarning {
+    pub enabled: bool,
+    pub seconds: u64,
+    pub pulse: bool,
+}
+
+impl Default for FinalWarning {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            seconds: 10,
+            pulse: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mqtt {
+    /// "host" or "host:port", the port defaults to 1883
+    pub broker: String,
+    pub topic: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+/// What a keyboard shortcut does. Tab, Shift+Tab and Escape aren't rebindable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    OpenPalette,
+    AddTimer,
+    UndoDelete,
+    DeleteFocused,
+}
+
+impl Action {
+    pub const ALL: [Action; 4] = [
+        Self::OpenPalette,
+        Self::AddTimer,
+        Self::UndoDelete,
+        Self::DeleteFocused,
+    ];
+
+    pub fn default_shortcuts(self) -> Vec<String> {
+        let shortcuts: &[&str] = match self {
+            Self::OpenPalette => &["Ctrl+K"],
+            Self::AddTimer => &["Ctrl+N"],
+            Self::UndoDelete => &["Ctrl+Z"],
+            Self::DeleteFocused => &["Delete", "Backspace"],
+        };
+        shortcuts
+            .iter()
+            .map(|shortcut| shortcut.to_string())
+            .collect()
+    }
+}
+
+/// How a key press is written in `Config::shortcuts`, like "Ctrl+Shift+N". Ctrl
+/// stands for Cmd on macOS. `None` for presses of a modifier alone.
+pub fn shortcut_name(key: &keyboard::Key, modifiers: keyboard::Modifiers) -> Option<String> {
+    use keyboard::key::Named;
+
+    let key = match key {
+        keyboard::Key::Named(Named::Control | Named::Shift | Named::Alt | Named::Super) => {
+            return None
+        }
+        keyboard::Key::Named(named) => format!("{named:?}"),
+        keyboard::Key::Character(character) => character.to_uppercase(),
+        keyboard::Key::Unidentified => return None,
+    };
+    let mut name = String::new();
+    if modifiers.command() {
+        name.push_str("Ctrl+");
+    }
+    if modifiers.alt() {
+        name.push_str("Alt+");
+    }
+    if modifiers.shift() {
+        name.push_str("Shift+");
+    }
+    name.push_str(&key);
+    Some(name)
+}
+
+/// Appearance settings saved together under a name
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewPreset {
+    pub name: String,
+    pub theme: ThemeChoice,
+    pub accent: Option<String>,
+    pub scale_factor: f64,
+}
+
+impl Display for ViewPreset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ThemeChoice {
+    System,
+    Dark,
+    Light,
+    HighContrast,
+}
+
+impl ThemeChoice {
+    pub const ALL: [ThemeChoice; 4] = [Self::System, Self::Dark, Self::Light, Self::HighContrast];
+}
+
+impl Display for ThemeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::System => write!(f, "{}", t("theme_system")),
+            Self::Dark => write!(f, "{}", t("theme_dark")),
+            Self::Light => write!(f, "{}", t("theme_light")),
+            Self::HighContrast => write!(f, "{}", t("theme_high_contrast")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SortOrder {
+    Created,
+    Name,
+    Remaining,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 3] = [Self::Created, Self::Name, Self::Remaining];
+}
+
+impl Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Created => write!(f, "{}", t("sort_created")),
+            Self::Name => write!(f, "{}", t("sort_name")),
+            Self::Remaining => write!(f, "{}", t("sort_remaining")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum AfterFinish {
+    /// Reset to its duration, ready to run again
+    Keep,
+    Remove,
+}
+
+impl AfterFinish {
+    pub const ALL: [AfterFinish; 2] = [Self::Keep, Self::Remove];
+
+    /// Deals with a finished timer once it's dismissed. Returns true when it's to be
+    /// removed, which like saving is up to the caller.
+    pub fn apply(self, timer: &mut Timer) -> bool {
+        match self {
+            Self::Keep => {
+                timer.reset();
+                false
+            }
+            Self::Remove => true,
+        }
+    }
+}
+
+impl Display for AfterFinish {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Keep => write!(f, "{}", t("keep_for_reuse")),
+            Self::Remove => write!(f, "{}", t("remove")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Urgency {
+    Low,
+    Normal,
+    Critical,
+}
+
+impl Urgency {
+    pub const ALL: [Urgency; 3] = [Self::Low, Self::Normal, Self::Critical];
+}
+
+impl Display for Urgency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Low => write!(f, "{}", t("urgency_low")),
+            Self::Normal => write!(f, "{}", t("urgency_normal")),
+            Self::Critical => write!(f, "{}", t("urgency_critical")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NotificationTimeout {
+    /// Whatever the notification server does by default
+    Auto,
+    Seconds(u32),
+    Sticky,
+}
+
+impl NotificationTimeout {
+    pub const ALL: [NotificationTimeout; 5] = [
+        Self::Auto,
+        Self::Seconds(5),
+        Self::Seconds(10),
+        Self::Seconds(30),
+        Self::Sticky,
+    ];
+}
+
+impl Display for NotificationTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "{}", t("timeout_auto")),
+            Self::Seconds(seconds) => write!(
+                f,
+                "{}",
+                t_fmt("timeout_seconds", &[("seconds", &seconds.to_string())])
+            ),
+            Self::Sticky => write!(f, "{}", t("timeout_sticky")),
+        }
+    }
+}
+
+impl Config {
+    pub const SCALE_FACTOR_RANGE: std::ops::RangeInclusive<f64> = 0.5..=3.0;
+
+    pub fn load() -> Self {
+        Self::load_from(&path())
+    }
+
+    fn load_from(path: &Path) -> Self {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        toml::from_str(&content).unwrap_or_else(|err| {
+            log::warn!("failed to parse config, using defaults: {err}");
+            Self::default()
+        })
+    }
+
+    /// Hand-edited config files can hold anything, keep the UI usable
+    pub fn scale_factor(&self) -> f64 {
+        if self.scale_factor.is_finite() {
+            self.scale_factor.clamp(
+                *Self::SCALE_FACTOR_RANGE.start(),
+                *Self::SCALE_FACTOR_RANGE.end(),
+            )
+        } else {
+            1.0
+        }
+    }
+
+    /// Key combinations of every action, in `Action::ALL` order
+    pub fn shortcuts(&self) -> Vec<(Action, Vec<String>)> {
+        let mut taken: Vec<String> = Vec::new();
+        Action::ALL
+            .into_iter()
+            .map(|action| {
+                let shortcuts = match self.shortcuts.get(&action) {
+                    Some(shortcuts)
+                        if !shortcuts.is_empty()
+                            && !shortcuts.iter().any(|shortcut| taken.contains(shortcut)) =>
+                    {
+                        shortcuts.clone()
+                    }
+                    Some(_) => {
+                        log::warn!("shortcut of {action:?} is empty or taken, using the default");
+                        action.default_shortcuts()
+                    }
+                    None => action.default_shortcuts(),
+                };
+                taken.extend(shortcuts.iter().cloned());
+                (action, shortcuts)
+            })
+            .collect()
+    }
+
+    /// The current appearance under `name`
+    pub fn view_preset(&self, name: String) -> ViewPreset {
+        ViewPreset {
+            name,
+            theme: self.theme,
+            accent: self.accent.clone(),
+            scale_factor: self.scale_factor(),
+        }
+    }
+
+    /// The saved preset the current appearance matches, if any
+    pub fn active_view_preset(&self) -> Option<&ViewPreset> {
+        self.view_presets
+            .iter()
+            .find(|preset| **preset == self.view_preset(preset.name.clone()))
+    }
+
+    pub fn apply_view_preset(&mut self, preset: &ViewPreset) {
+        self.theme = preset.theme;
+        self.accent = preset.accent.clone();
+        self.scale_factor = preset.scale_factor;
+    }
+
+    pub fn tick_interval(&self) -> Duration {
+        self.tick_interval
+            .clamp(Duration::from_secs(1), Duration::from_secs(60))
+    }
+
+    pub fn save(&self) {
+        self.save_to(&path());
+    }
+
+    fn save_to(&self, path: &Path) {
+        let result = std::fs::create_dir_all(path.parent().unwrap())
+            .and_then(|_| {
+                toml::to_string_pretty(self)
+                    .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+            })
+            .and_then(|content| std::fs::write(path, content));
+        if let Err(err) = result {
+            log::error!("failed to save config: {err}");
+        }
+    }
+}
+
+fn path() -> PathBuf {
+    // Since I don't care about Windows
+    #[allow(deprecated)]
+    let mut path = std::env::home_dir().unwrap_or_default();
+    path.push(std::path::Path::new(".config/oxyclock/config.toml"));
+    path
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Percentage {
+    Remaining,
+    Done,
+}
+
+impl Percentage {
+    pub const ALL: [Percentage; 2] = [Self::Remaining, Self::Done];
+}
+
+impl Display for Percentage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Remaining => write!(f, "{}", t("percentage_remaining")),
+            Self::Done => write!(f, "{}", t("percentage_done")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Empty directory of its own for each test
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("oxyclock-config-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn to_toml(config: &Config) -> String {
+        toml::to_string_pretty(config).unwrap()
+    }
+
+    #[test]
+    fn default_parses_back() {
+        let default = to_toml(&Config::default());
+        let parsed: Config = toml::from_str(&default).unwrap();
+        assert_eq!(to_toml(&parsed), default);
+    }
+
+    #[test]
+    fn missing_file_gives_the_defaults() {
+        let path = scratch_dir("missing").join("config.toml");
+        assert_eq!(
+            to_toml(&Config::load_from(&path)),
+            to_toml(&Config::default())
+        );
+    }
+
+    #[test]
+    fn corrupt_file_gives_the_defaults() {
+        let path = scratch_dir("corrupt").join("config.toml");
+        std::fs::write(&path, "show_percentage = [not toml").unwrap();
+        assert_eq!(
+            to_toml(&Config::load_from(&path)),
+            to_toml(&Config::default())
+        );
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_the_defaults() {
+        let path = scratch_dir("partial").join("config.toml");
+        std::fs::write(&path, "show_percentage = true\n").unwrap();
+        let config = Config::load_from(&path);
+        assert!(config.show_percentage);
+        assert_eq!(
+            to_toml(&Config {
+                show_percentage: false,
+                ..config
+            }),
+            to_toml(&Config::default())
+        );
+    }
+
+    #[test]
+    fn saved_config_loads_back() {
+        let path = scratch_dir("saved").join("nested").join("config.toml");
+        let config = Config {
+            show_percentage: true,
+            last_duration: Duration::from_secs(90),
+            ..Config::default()
+        };
+        config.save_to(&path);
+        assert_eq!(to_toml(&Config::load_from(&path)), to_toml(&config));
+    }
+
+    /// A five second countdown run to the end and dismissed
+    fn finished_timer() -> Timer {
+        let mut timer = Timer {
+            seconds: "05".to_string(),
+            ..Timer::default()
+        };
+        timer.start(Duration::MAX).unwrap();
+        let started = timer.last_tick.unwrap();
+        assert!(timer.tick(started + Duration::from_secs(5)));
+        timer.pause();
+        timer
+    }
+
+    #[test]
+    fn keeping_a_finished_timer_readies_it_for_another_run() {
+        let mut timer = finished_timer();
+        assert!(!AfterFinish::Keep.apply(&mut timer));
+        assert_eq!(timer.seconds, "05");
+        assert_eq!(timer.time, Duration::ZERO);
+        assert_eq!(timer.start(Duration::MAX).unwrap(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn removing_a_finished_timer_leaves_it_to_the_caller() {
+        let mut timer = finished_timer();
+        assert!(AfterFinish::Remove.apply(&mut timer));
+    }
+}
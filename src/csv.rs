@@ -0,0 +1,183 @@
+use chrono::{DateTime, Local, SecondsFormat};
+
+use crate::{
+    history::Record,
+    timer::{Timer, MAX_DURATION},
+    utils,
+};
+
+pub struct CsvImport {
+    pub timers: Vec<Timer>,
+    /// 1-based line numbers of rows that couldn't be parsed
+    pub skipped: Vec<usize>,
+}
+
+/// Parses `name,duration` rows, the header row is optional. Malformed rows are
+/// skipped and reported instead of failing the whole import.
+pub fn import_timers(content: &str) -> CsvImport {
+    let mut import = CsvImport {
+        timers: Vec::new(),
+        skipped: Vec::new(),
+    };
+
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || (index == 0 && line.eq_ignore_ascii_case("name,duration")) {
+            continue;
+        }
+
+        // Durations never contain commas, so names are free to
+        let parsed = line.rsplit_once(',').and_then(|(name, duration)| {
+            // The fields couldn't hold it, nor could a timer made to match it
+            let duration = utils::parse_duration(duration).filter(|&d| d <= MAX_DURATION)?;
+            let mut timer = Timer {
+                name: unquote(name.trim()),
+                ..Timer::default()
+            };
+            timer.set_hms(duration);
+            Some(timer)
+        });
+
+        match parsed {
+            Some(timer) => import.timers.push(timer),
+            None => import.skipped.push(index + 1),
+        }
+    }
+
+    import
+}
+
+/// A quoted field without its quotes and with `""` back to `"`
+fn unquote(field: &str) -> String {
+    match field
+        .strip_prefix('"')
+        .and_then(|field| field.strip_suffix('"'))
+    {
+        Some(quoted) => quoted.replace("\"\"", "\""),
+        None => field.to_string(),
+    }
+}
+
+/// Writes `name,duration,finished_at` rows with RFC 3339 timestamps. An empty
+/// history still produces the header.
+pub fn export_history(records: &[Record]) -> String {
+    let mut content = String::from("name,duration,finished_at\n");
+    for record in records {
+        let finished_at: DateTime<Local> = record.finished_at.into();
+        content.push_str(&format!(
+            "{},{},{}\n",
+            escape(&record.name),
+            utils::format_hms(record.duration),
+            finished_at.to_rfc3339_opts(SecondsFormat::Secs, false)
+        ));
+    }
+    content
+}
+
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn durations(import: &CsvImport) -> Vec<(&str, Duration)> {
+        import
+            .timers
+            .iter()
+            .map(|timer| {
+                let duration = timer.get_duration(MAX_DURATION).unwrap();
+                (timer.name.as_str(), duration)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn imports_well_formed_rows() {
+        let import = import_timers(
+            "name,duration\nTea,00:03:00\n\"Pasta, al dente\",9 min\nPlank,1m 30s\nNap,20\n",
+        );
+        assert_eq!(
+            durations(&import),
+            [
+                ("Tea", Duration::from_secs(180)),
+                ("Pasta, al dente", Duration::from_secs(540)),
+                ("Plank", Duration::from_secs(90)),
+                ("Nap", Duration::from_secs(1200)),
+            ]
+        );
+        assert!(import.skipped.is_empty());
+    }
+
+    #[test]
+    fn skips_broken_rows_and_keeps_the_rest() {
+        let import = import_timers("Tea,00:03:00\nno duration\nEggs,soon\n\nRice,18:00\n");
+        assert_eq!(
+            durations(&import),
+            [
+                ("Tea", Duration::from_secs(180)),
+                ("Rice", Duration::from_secs(1080)),
+            ]
+        );
+        assert_eq!(import.skipped, [2, 3]);
+    }
+
+    #[test]
+    fn imports_quoted_names_with_quotes_in_them() {
+        let import = import_timers("\"Tea, \"\"green\"\"\",00:03:05\n\"\"\"Eggs\"\"\",7\n");
+        assert_eq!(
+            durations(&import),
+            [
+                ("Tea, \"green\"", Duration::from_secs(185)),
+                ("\"Eggs\"", Duration::from_secs(420)),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_durations_over_the_maximum() {
+        let import = import_timers("Long,999:59:59\nLonger,1000:00:00\n");
+        assert_eq!(durations(&import), [("Long", MAX_DURATION)]);
+        assert_eq!(import.skipped, [2]);
+    }
+
+    #[test]
+    fn imported_timers_get_fresh_ids() {
+        let import = import_timers("Tea,3\nTea,3\n");
+        assert_ne!(import.timers[0].id, import.timers[1].id);
+    }
+
+    #[test]
+    fn exports_escaped_names() {
+        let finished_at = std::time::SystemTime::now();
+        let content = export_history(&[Record {
+            name: "Tea, \"green\"".to_string(),
+            duration: Duration::from_secs(185),
+            finished_at,
+            mode: crate::timer::Mode::Countdown,
+        }]);
+        let finished_at: DateTime<Local> = finished_at.into();
+        assert_eq!(
+            content,
+            format!(
+                "name,duration,finished_at\n\"Tea, \"\"green\"\"\",00:03:05,{}\n",
+                finished_at.to_rfc3339_opts(SecondsFormat::Secs, false)
+            )
+        );
+        assert_eq!(export_history(&[]), "name,duration,finished_at\n");
+    }
+
+    #[test]
+    fn escaped_names_import_back() {
+        for name in ["Tea, \"green\"", "\"Eggs\"", "Plain"] {
+            let import = import_timers(&format!("{},00:03:05\n", escape(name)));
+            assert_eq!(durations(&import), [(name, Duration::from_secs(185))]);
+        }
+    }
+}
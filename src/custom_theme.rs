@@ -3,15 +3,88 @@ use iced::{
     Color,
 };
 
-pub fn arc_dark() -> theme::Theme {
+pub fn arc_dark(accent: Option<Color>) -> theme::Theme {
     theme::Theme::custom(
         "Arc-Dark".to_string(),
         Palette {
             background: Color::from_rgb(47.0 / 255.0, 52.0 / 255.0, 63.0 / 255.0),
             text: Color::from_rgb(211.0 / 255.0, 218.0 / 255.0, 227.0 / 255.0),
-            primary: Color::from_rgb(82.0 / 255.0, 148.0 / 255.0, 226.0 / 255.0),
+            primary: accent.unwrap_or(Color::from_rgb(82.0 / 255.0, 148.0 / 255.0, 226.0 / 255.0)),
             success: Color::from_rgb(155.0 / 255.0, 89.0 / 255.0, 182.0 / 255.0),
             danger: Color::from_rgb(220.0 / 255.0, 50.0 / 255.0, 47.0 / 255.0),
         },
     )
 }
+
+pub fn arc_light(accent: Option<Color>) -> theme::Theme {
+    theme::Theme::custom(
+        "Arc-Light".to_string(),
+        Palette {
+            background: Color::from_rgb(245.0 / 255.0, 246.0 / 255.0, 247.0 / 255.0),
+            text: Color::from_rgb(92.0 / 255.0, 97.0 / 255.0, 108.0 / 255.0),
+            primary: accent.unwrap_or(Color::from_rgb(82.0 / 255.0, 148.0 / 255.0, 226.0 / 255.0)),
+            success: Color::from_rgb(155.0 / 255.0, 89.0 / 255.0, 182.0 / 255.0),
+            danger: Color::from_rgb(252.0 / 255.0, 65.0 / 255.0, 56.0 / 255.0),
+        },
+    )
+}
+
+const HIGH_CONTRAST: &str = "High-Contrast";
+
+pub fn high_contrast() -> theme::Theme {
+    theme::Theme::custom(
+        HIGH_CONTRAST.to_string(),
+        Palette {
+            background: Color::BLACK,
+            text: Color::WHITE,
+            primary: Color::from_rgb(1.0, 215.0 / 255.0, 0.0),
+            success: Color::from_rgb(0.0, 1.0, 0.0),
+            danger: Color::from_rgb(1.0, 0.0, 0.0),
+        },
+    )
+}
+
+pub fn is_high_contrast(theme: &theme::Theme) -> bool {
+    theme.to_string() == HIGH_CONTRAST
+}
+
+/// Alpha-scaled surfaces wash out in high contrast, which gets solid
+/// background surfaces instead
+pub fn faded(theme: &theme::Theme, color: Color, alpha: f32) -> Color {
+    if is_high_contrast(theme) {
+        theme.palette().background
+    } else {
+        color.scale_alpha(alpha)
+    }
+}
+
+pub fn faded_text(theme: &theme::Theme, alpha: f32) -> Color {
+    if is_high_contrast(theme) {
+        theme.palette().text
+    } else {
+        theme.palette().text.scale_alpha(alpha)
+    }
+}
+
+pub fn border_color(theme: &theme::Theme) -> Color {
+    if is_high_contrast(theme) {
+        theme.palette().text
+    } else {
+        theme.palette().background.scale_alpha(0.5)
+    }
+}
+
+pub fn parse_hex(hex: &str) -> Option<Color> {
+    let hex = hex.trim().strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+pub fn to_hex(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
@@ -0,0 +1,192 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::{
+    cli,
+    config::Config,
+    history, instance, mqtt,
+    timer::{self, Timer},
+    utils,
+};
+
+/// Set on SIGINT and SIGTERM, the loop saves the timers and returns once it sees it
+static STOPPING: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_stop(_: libc::c_int) {
+    STOPPING.store(true, Ordering::Relaxed);
+}
+
+/// `oxyclock --daemon`: counts down the timers of the active profile without a
+/// window, with the same notifications and sound as the GUI. Other processes
+/// control it through `oxyclock status|start|pause|reset`.
+pub fn run(config: Config) {
+    let mut timers = crate::load_timers(&config.profile);
+    // Whatever ran while nothing was counting catches up on the time that passed,
+    // scheduled timers count down to their next occurrence
+    for timer in &mut timers {
+        if timer.arm_schedule(chrono::Local::now()) {
+            continue;
+        }
+        if timer.state == timer::State::Running {
+            timer.catch_up(SystemTime::now());
+        } else {
+            timer.last_tick = Some(Instant::now());
+        }
+    }
+    let timers = Arc::new(Mutex::new(timers));
+    let publisher = config.mqtt.as_ref().and_then(mqtt::Publisher::connect);
+
+    let served = {
+        let timers = Arc::clone(&timers);
+        let config = config.clone();
+        instance::serve(move |request| handle(&config, &mut timers.lock().unwrap(), request))
+    };
+    if !served {
+        eprintln!("oxyclock is already running");
+        return;
+    }
+
+    // The default action would end the process with the countdowns unsaved
+    // SAFETY: the handler only stores to an atomic, which is async-signal-safe
+    unsafe {
+        libc::signal(libc::SIGINT, request_stop as libc::sighandler_t);
+        libc::signal(libc::SIGTERM, request_stop as libc::sighandler_t);
+    }
+
+    let mut history = history::load();
+    loop {
+        if !wait(config.tick_interval()) {
+            // Saved with the time they had left at the last tick, the next start
+            // catches up from there
+            save(&config, &timers.lock().unwrap());
+            return;
+        }
+        let mut timers = timers.lock().unwrap();
+        let now = Instant::now();
+        // Countdowns don't care, but schedules are meant for a time of day
+        if timers
+            .iter()
+            .any(|timer| timer.clock_jumped(now, SystemTime::now()))
+            && timer::rearm_schedules(&mut timers, chrono::Local::now())
+        {
+            log::info!("the system clock changed, re-armed schedules");
+            save(&config, &timers);
+        }
+        let mut finished = false;
+        let mut removed = Vec::new();
+        for (position, timer) in timers.iter_mut().enumerate() {
+            let before = timer.time;
+            if !timer.tick(now) {
+                if config.announce && !timer.silent {
+                    let milestones = timer.announce_at.as_deref().unwrap_or(&config.announce_at);
+                    if let Some(milestone) = timer::crossed(before, timer.time, milestones) {
+                        utils::announce(milestone);
+                    }
+                }
+                continue;
+            }
+            finished = true;
+
+            let name = timer.display_name(position);
+            let (webhook, break_started) =
+                crate::finish_timer(timer, &name, &mut history, publisher.as_ref(), &config);
+            if let Some(webhook) = webhook {
+                webhook.spawn();
+            }
+            if !timer.silent {
+                let repeats = if break_started {
+                    1
+                } else {
+                    timer.alarm_repeats.unwrap_or(config.alarm_repeats)
+                };
+                let path = config.sound_path.clone();
+                let volume = config.volume;
+                std::thread::spawn(move || {
+                    let repeat = utils::Repeat::Times(repeats);
+                    if let Err(err) =
+                        utils::play_notification_sound(path.as_deref(), volume, &repeat)
+                    {
+                        log::warn!("failed to play notification sound: {err}");
+                    }
+                });
+            }
+            if !break_started && timer.arm_schedule(chrono::Local::now()) {
+                continue;
+            }
+            if break_started {
+                continue;
+            }
+            // Nobody dismisses anything here, without holding it's done as soon as it finished
+            if config.hold_finished {
+                timer.state = timer::State::Finished;
+            } else if config.after_finish.apply(timer) {
+                removed.push(timer.id);
+            }
+        }
+        timers.retain(|timer| !removed.contains(&timer.id));
+
+        // Like in the GUI, the countdown itself isn't written every tick
+        if finished {
+            save(&config, &timers);
+        }
+    }
+}
+
+/// Sleeps for `duration` in short steps. Returns false as soon as a stop was requested.
+fn wait(duration: Duration) -> bool {
+    let until = Instant::now() + duration;
+    while !STOPPING.load(Ordering::Relaxed) {
+        let left = until.saturating_duration_since(Instant::now());
+        if left.is_zero() {
+            return true;
+        }
+        std::thread::sleep(left.min(Duration::from_millis(100)));
+    }
+    false
+}
+
+fn handle(config: &Config, timers: &mut [Timer], request: &str) -> String {
+    let (command, target) = request.split_once(' ').unwrap_or((request, ""));
+    match command {
+        "status" => return cli::to_json(timers),
+        "start" | "pause" | "reset" => {}
+        _ => return format!("unknown command \"{command}\"\n"),
+    }
+
+    let target = target.trim();
+    let Some(position) = timers.iter().enumerate().position(|(index, timer)| {
+        timer.id.to_string() == target || timer.display_name(index).eq_ignore_ascii_case(target)
+    }) else {
+        return format!("no timer named \"{target}\"\n");
+    };
+    let timer = &mut timers[position];
+    let reply = match command {
+        "start" => match timer.start(config.max_duration) {
+            Ok(duration) => format!("started, {} left\n", utils::format_hms(duration)),
+            Err(err) => format!("can't start: {err}\n"),
+        },
+        // Pausing a finished or ringing timer would turn it back into a stopped one
+        "pause" if timer.state != timer::State::Running => return "not running\n".to_string(),
+        "pause" => {
+            timer.pause();
+            format!("paused, {} left\n", utils::format_hms(timer.time))
+        }
+        _ => {
+            timer.reset();
+            "reset\n".to_string()
+        }
+    };
+    save(config, timers);
+    reply
+}
+
+fn save(config: &Config, timers: &[Timer]) {
+    if let Err(err) = crate::write_timers(&config.profile, timers) {
+        log::error!("failed to save state: {err}");
+    }
+}
@@ -0,0 +1,143 @@
+use iced::{
+    mouse, touch,
+    widget::canvas::{self, event, path::Arc, Event, Frame, Geometry, Path, Stroke},
+    Point, Radians, Rectangle, Renderer, Theme,
+};
+use std::{f32::consts::PI, time::Duration};
+use uuid::Uuid;
+
+use crate::{custom_theme, Msg};
+
+/// Circular progress ring that doubles as a kitchen timer dial. While the timer is
+/// stopped, dragging around it sets the duration, snapped to whole minutes.
+pub struct Dial {
+    pub timer_id: Uuid,
+    /// Filled part of the ring, from 0.0 to 1.0
+    pub fraction: f32,
+    /// Duration of one full turn, `None` for a ring that only shows progress
+    pub turn: Option<Duration>,
+}
+
+impl Dial {
+    /// Duration for the pointer at `position`, measured clockwise from the top
+    fn duration_at(&self, turn: Duration, bounds: Rectangle, position: Point) -> Duration {
+        let center = bounds.center();
+        let angle = (position.x - center.x).atan2(center.y - position.y);
+        let fraction = angle.rem_euclid(2.0 * PI) / (2.0 * PI);
+        let minutes = (fraction * turn.as_secs_f32() / 60.0).round() as u64;
+        Duration::from_secs(minutes * 60)
+    }
+}
+
+impl canvas::Program<Msg> for Dial {
+    /// Whether a drag started on the dial
+    type State = bool;
+
+    fn update(
+        &self,
+        dragging: &mut bool,
+        event: Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (event::Status, Option<Msg>) {
+        let Some(turn) = self.turn else {
+            return (event::Status::Ignored, None);
+        };
+
+        let position = match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    return (event::Status::Ignored, None);
+                };
+                *dragging = true;
+                position
+            }
+            Event::Touch(touch::Event::FingerPressed { position, .. })
+                if bounds.contains(position) =>
+            {
+                *dragging = true;
+                position
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position })
+            | Event::Touch(touch::Event::FingerMoved { position, .. })
+                if *dragging =>
+            {
+                position
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+            | Event::Touch(touch::Event::FingerLifted { .. } | touch::Event::FingerLost { .. })
+                if *dragging =>
+            {
+                *dragging = false;
+                return (event::Status::Captured, None);
+            }
+            _ => return (event::Status::Ignored, None),
+        };
+
+        let duration = self.duration_at(turn, bounds, position);
+        (
+            event::Status::Captured,
+            Some(Msg::Dial((self.timer_id, duration))),
+        )
+    }
+
+    fn draw(
+        &self,
+        _dragging: &bool,
+        renderer: &Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: mouse::Cursor,
+    ) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = Frame::new(renderer, bounds.size());
+        let center = frame.center();
+        let width = 6.0;
+        let radius = frame.width().min(frame.height()) / 2.0 - width;
+
+        frame.stroke(
+            &Path::circle(center, radius),
+            Stroke::default()
+                .with_width(width)
+                .with_color(custom_theme::border_color(theme)),
+        );
+
+        let fraction = self.fraction.clamp(0.0, 1.0);
+        if fraction > 0.0 {
+            let start = -PI / 2.0;
+            let arc = Path::new(|builder| {
+                builder.arc(Arc {
+                    center,
+                    radius,
+                    start_angle: Radians(start),
+                    end_angle: Radians(start + fraction * 2.0 * PI),
+                })
+            });
+            frame.stroke(
+                &arc,
+                Stroke::default()
+                    .with_width(width)
+                    .with_color(palette.primary.base.color),
+            );
+        }
+
+        vec![frame.into_geometry()]
+    }
+
+    fn mouse_interaction(
+        &self,
+        dragging: &bool,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> mouse::Interaction {
+        if self.turn.is_none() {
+            mouse::Interaction::default()
+        } else if *dragging {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(bounds) {
+            mouse::Interaction::Grab
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    time::{Duration, SystemTime},
+};
+
+use crate::{timer, utils};
+
+/// A finished countdown or stopped stopwatch, kept in `history.json` next to the
+/// timer state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Record {
+    pub name: String,
+    pub duration: Duration,
+    pub finished_at: SystemTime,
+    /// Older records are all countdowns
+    #[serde(default)]
+    pub mode: timer::Mode,
+}
+
+pub fn load() -> Vec<Record> {
+    let path = utils::state_dir().join("history.json");
+    let Ok(file) = File::open(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|err| {
+        log::error!("failed to parse {}: {err}", path.display());
+        Vec::new()
+    })
+}
+
+pub fn save(records: &[Record]) -> io::Result<()> {
+    std::fs::create_dir_all(utils::state_dir())?;
+    let file = File::create(utils::state_dir().join("history.json"))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, records)?;
+    writer.flush()
+}
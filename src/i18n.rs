@@ -0,0 +1,318 @@
+use std::sync::OnceLock;
+
+type Catalog = [(&'static str, &'static str)];
+
+/// Catalogs by language code. A translation only needs the keys it changes,
+/// the rest falls back to English.
+const CATALOGS: &[(&str, &Catalog)] = &[("en", EN)];
+
+static CATALOG: OnceLock<&'static Catalog> = OnceLock::new();
+static RTL: OnceLock<bool> = OnceLock::new();
+
+const RTL_LANGUAGES: [&str; 4] = ["ar", "fa", "he", "ur"];
+
+const EN: &Catalog = &[
+    ("accent", "Accent"),
+    ("add_template", "Add template"),
+    ("add_timer", "Add timer"),
+    ("add_timer_shortcut", "Add timer (Ctrl+N)"),
+    ("after_finish", "Done timers, once dismissed"),
+    ("alarm_plays", "Alarm plays"),
+    ("announce_at", "Announce at, e.g. 5m, 1m, 10s"),
+    ("back", "Back"),
+    ("break_after", "Break after"),
+    ("break_over", "Break after {name} is over"),
+    (
+        "break_over_body",
+        "Time to get back to it, next up is {name} for {duration}",
+    ),
+    ("bundled_alarm", "Bundled alarm"),
+    ("by_timer", "By timer"),
+    ("cancel_timer", "Cancel {name}"),
+    ("change", "Change"),
+    ("clear", "C"),
+    ("clock_24h", "24-hour clock"),
+    ("copy", "Copy"),
+    ("create", "Create"),
+    ("created", "created {time}"),
+    ("custom", "Custom"),
+    ("daily_goal", "Daily goal"),
+    ("daily_goal_count", "{count} timers"),
+    ("day_ago", "1 day ago"),
+    ("days_ago", "{count} days ago"),
+    ("decrease", "Decrease {field}"),
+    ("default", "Default"),
+    ("delete", "Delete"),
+    ("delete_focused", "Delete focused timer"),
+    ("delete_template", "Delete template"),
+    ("delete_timer", "Delete {name}"),
+    ("digit_entry", "Type the duration as digits"),
+    ("discard", "Discard"),
+    ("dismiss", "Dismiss"),
+    ("done", "Done!"),
+    ("done_selecting", "Done"),
+    ("duplicate_name", "Another timer has this name"),
+    ("duration", "Duration"),
+    ("duration_placeholder", "25:00 or 1h 30m"),
+    ("elapsed", "elapsed {time}"),
+    ("ends_at", "ends at {time}"),
+    ("expand", "Expand"),
+    ("export", "Export"),
+    ("export_history", "Export history"),
+    ("final_seconds", "Final seconds"),
+    ("flash_taskbar", "Flash taskbar"),
+    ("focus_time", "Focus time"),
+    ("fri", "F"),
+    ("go_to_timer", "Go to {name}"),
+    ("goal", "Goal"),
+    ("goal_progress", "{finished}/{goal} today"),
+    ("goal_reached", "Goal reached!"),
+    ("hide_note", "Hide note"),
+    ("hold_finished", "Hold done timers until dismissed"),
+    ("hours", "Hours"),
+    ("hours_ago", "{count} h ago"),
+    (
+        "hours_not_a_number",
+        "hours \"{value}\" is not a whole number",
+    ),
+    ("icon_placeholder", "Icon, an emoji or image path"),
+    ("import", "Import"),
+    ("import_csv", "Import CSV"),
+    ("increase", "Increase {field}"),
+    ("invalid_duration", "Invalid duration: {error}"),
+    ("just_now", "Just now"),
+    ("keep_for_reuse", "Keep for reuse"),
+    ("lap", "Lap"),
+    ("lap_number", "Lap {number}"),
+    (
+        "longer_than_max",
+        "{duration} is longer than the {max} maximum",
+    ),
+    ("mini", "Mini"),
+    ("minute_remaining", "1 minute remaining"),
+    ("minutes", "Minutes"),
+    ("minutes_ago", "{count} min ago"),
+    (
+        "minutes_not_a_number",
+        "minutes \"{value}\" is not a whole number",
+    ),
+    ("minutes_remaining", "{count} minutes remaining"),
+    ("minutes_short", "min"),
+    ("mon", "M"),
+    ("name", "Name"),
+    ("new_profile", "New profile"),
+    (
+        "no_audio_device",
+        "No audio output device found, alarms will only show a notification",
+    ),
+    ("no_audio_output", "No audio output device found"),
+    ("no_running_timers", "No running timers"),
+    (
+        "no_stats",
+        "No finished timers yet, your stats will show up here",
+    ),
+    (
+        "no_templates",
+        "No templates yet, add one to start timers from it",
+    ),
+    ("none", "None"),
+    ("note", "Note"),
+    ("nothing_to_paste", "The clipboard doesn't hold a timer"),
+    ("notification", "Notification"),
+    ("number_duplicates", "Number duplicate names"),
+    ("off", "Off"),
+    ("on_break", "On break"),
+    ("open_palette", "Open command palette"),
+    ("open_settings", "Open settings"),
+    ("open_stats", "Open stats"),
+    ("open_templates", "Open templates"),
+    ("over_max", "longer than the {max} maximum"),
+    ("palette_placeholder", "Type a command or timer name"),
+    ("paste", "Paste"),
+    ("pause_timer", "Pause {name}"),
+    ("pause_when_away", "Pause while locked or idle"),
+    ("percent_done", "{percent}% done"),
+    ("percent_remaining", "{percent}% remaining"),
+    ("percentage", "Percentage"),
+    ("percentage_done", "Done"),
+    ("percentage_remaining", "Remaining"),
+    ("pin_timer", "Pin {name}"),
+    ("play_test_sound", "Play test sound"),
+    ("preset_name", "Preset name"),
+    ("press_shortcut", "Press a key combination…"),
+    ("profile_name", "Profile name"),
+    ("profile_switched", "Switched to {name}"),
+    ("pulse", "Pulse"),
+    (
+        "quick_add_no_duration",
+        "Couldn't find a duration in \"{input}\"",
+    ),
+    (
+        "quick_add_placeholder",
+        "Quick add, e.g. \"Tea 3:00\" or \"Focus 25m start\"",
+    ),
+    ("read_failed", "Failed to read file: {error}"),
+    ("reduce_motion", "Reduce motion"),
+    ("remove", "Remove"),
+    ("reset_shortcuts", "Reset shortcuts"),
+    ("reset_timer", "Reset {name}"),
+    ("resume", "Resume"),
+    (
+        "resume_many",
+        "{count} timers were running when oxyclock closed. Resume them with the time that passed since?",
+    ),
+    (
+        "resume_one",
+        "A timer was running when oxyclock closed. Resume it with the time that passed since?",
+    ),
+    ("retry", "Retry"),
+    ("ring_until_stopped", "Ring until stopped"),
+    ("round_to", "Round to"),
+    ("sat", "S"),
+    ("save", "Save"),
+    ("save_failed", "Failed to save timers: {error}"),
+    ("save_timer", "Save {name}"),
+    ("second_remaining", "1 second remaining"),
+    ("seconds", "Seconds"),
+    ("seconds_ago", "{count} s ago"),
+    (
+        "seconds_not_a_number",
+        "seconds \"{value}\" is not a whole number",
+    ),
+    ("seconds_remaining", "{count} seconds remaining"),
+    ("select", "Select"),
+    ("selected", "{count} selected"),
+    ("sessions_exported", "Exported {count} sessions"),
+    ("set_duration_first", "Set a duration first"),
+    ("settings", "Settings"),
+    ("shortcut_taken", "{shortcut} is already used to {action}"),
+    ("shortcuts", "Shortcuts"),
+    ("shortcuts_reset", "Shortcuts reset to their defaults"),
+    ("show_created", "Creation time"),
+    ("show_elapsed", "Elapsed time"),
+    ("show_ends_at", "End time"),
+    ("silent", "Silent"),
+    ("sort_created", "Creation order"),
+    ("sort_name", "Name A–Z"),
+    ("sort_remaining", "Least time remaining"),
+    ("sound", "Sound"),
+    ("sound_crashed", "Sound playback stopped unexpectedly"),
+    ("sound_failed", "Failed to play sound: {error}"),
+    (
+        "sound_unplayable",
+        "Can't play {path} ({error}), the bundled alarm rings instead",
+    ),
+    ("start", "Start"),
+    ("start_all", "Start all"),
+    ("start_sound", "Start sound"),
+    ("start_timer", "Start {name}"),
+    ("stats", "Stats"),
+    ("stop", "Stop"),
+    ("stop_alarm", "Stop alarm"),
+    ("stop_stopwatch", "Stop {name}"),
+    ("stopwatch", "Stopwatch"),
+    ("sun", "S"),
+    ("switch_theme", "Switch theme"),
+    (
+        "take_a_break",
+        "Take a break for {duration}, then {name} again for {next}",
+    ),
+    ("templates", "Templates"),
+    ("theme", "Theme"),
+    ("theme_dark", "Dark"),
+    ("theme_high_contrast", "High contrast"),
+    ("theme_light", "Light"),
+    ("theme_system", "System"),
+    ("this_week", "This week"),
+    ("thu", "T"),
+    ("tick_sound", "Tick sound"),
+    ("timeout_auto", "Auto"),
+    ("timeout_seconds", "{seconds} s"),
+    ("timeout_sticky", "Until dismissed"),
+    ("timer_copied", "Copied to the clipboard"),
+    ("timer_deleted", "Deleted {name}"),
+    ("timer_done", "{name} is done!"),
+    ("timer_done_body", "Your timer has finished"),
+    ("timer_pasted", "Pasted a timer"),
+    ("timer_saved", "Saved"),
+    ("timers_deleted", "Deleted {count} timers"),
+    ("timers_imported", "Imported {count} timers"),
+    (
+        "timers_imported_skipped",
+        "Imported {count} timers, skipped lines {lines}",
+    ),
+    ("today", "Today"),
+    ("touch_mode", "Touch mode"),
+    ("tue", "T"),
+    ("ui_scale", "UI scale"),
+    ("undo", "Undo"),
+    ("undo_delete", "Undo delete"),
+    ("unnamed_timer", "Timer {number}"),
+    ("unnamed_timers", "Unnamed timers"),
+    ("unpin_timer", "Unpin {name}"),
+    (
+        "unrecognized_duration",
+        "Unrecognized duration, the last valid one is kept",
+    ),
+    ("urgency_critical", "Critical"),
+    ("urgency_low", "Low"),
+    ("urgency_normal", "Normal"),
+    ("use_template", "Use"),
+    ("view_preset", "View preset"),
+    ("view_preset_saved", "Saved view preset {name}"),
+    ("voice_countdown", "Voice countdown"),
+    ("volume", "Volume"),
+    ("webhook_failed", "Webhook failed: {error}"),
+    ("webhook_url", "Webhook URL"),
+    ("wed", "W"),
+    ("write_failed", "Failed to write file: {error}"),
+];
+
+/// Picks the catalog for `locale` ("de", "pt_BR"...), or for `LANG` when unset.
+/// The layout direction follows the language unless `rtl` forces it.
+pub fn init(locale: Option<&str>, rtl: Option<bool>) {
+    let locale = locale
+        .map(str::to_string)
+        .or_else(|| std::env::var("LANG").ok())
+        .unwrap_or_default();
+    let language = locale
+        .split(['_', '-', '.'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let catalog = CATALOGS
+        .iter()
+        .find(|(code, _)| *code == language)
+        .map_or(EN, |(_, catalog)| *catalog);
+    let _ = CATALOG.set(catalog);
+    let _ = RTL.set(rtl.unwrap_or(RTL_LANGUAGES.contains(&language.as_str())));
+}
+
+pub fn is_rtl() -> bool {
+    RTL.get().copied().unwrap_or(false)
+}
+
+/// The string for `key` in the current language
+pub fn t(key: &'static str) -> &'static str {
+    let lookup = |catalog: &Catalog| {
+        catalog
+            .iter()
+            .find(|(k, _)| *k == key)
+            .map(|(_, value)| *value)
+    };
+    CATALOG
+        .get()
+        .and_then(|catalog| lookup(catalog))
+        .or_else(|| lookup(EN))
+        .unwrap_or_else(|| {
+            log::warn!("missing translation key {key}");
+            key
+        })
+}
+
+/// Like `t`, filling `{name}` style placeholders from `args`
+pub fn t_fmt(key: &'static str, args: &[(&str, &str)]) -> String {
+    args.iter().fold(t(key).to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), value)
+    })
+}
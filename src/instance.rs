@@ -0,0 +1,90 @@
+use iced::futures::{channel::mpsc, stream, Stream, StreamExt};
+use std::{
+    io::{self, BufRead, BufReader, Read, Write},
+    net::Shutdown,
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    sync::Mutex,
+};
+
+use crate::{utils, Msg};
+
+/// Handed from `acquire` to the subscription started once the app runs
+static FOCUS_REQUESTS: Mutex<Option<mpsc::UnboundedReceiver<()>>> = Mutex::new(None);
+
+fn socket_path() -> PathBuf {
+    utils::local_state_dir().join("oxyclock.sock")
+}
+
+/// Makes this process the running instance. Returns false when another one
+/// already answers on the socket, in which case it was asked to show its window.
+pub fn acquire() -> bool {
+    if let Ok(mut stream) = UnixStream::connect(socket_path()) {
+        if let Err(err) = stream.write_all(b"focus\n") {
+            log::warn!("failed to reach the running instance: {err}");
+        }
+        return false;
+    }
+
+    let (sender, receiver) = mpsc::unbounded();
+    *FOCUS_REQUESTS.lock().unwrap() = Some(receiver);
+    serve(move |request| {
+        if request == "focus" {
+            let _ = sender.unbounded_send(());
+        }
+        String::new()
+    })
+}
+
+/// Answers requests on the socket from a background thread, one line in and the
+/// reply of `handle` out per connection. Returns false when another instance
+/// already listens there.
+pub fn serve(handle: impl Fn(&str) -> String + Send + 'static) -> bool {
+    let path = socket_path();
+    if UnixStream::connect(&path).is_ok() {
+        return false;
+    }
+
+    // Nobody is listening, so a socket left at the path belongs to a crashed instance
+    let _ = std::fs::remove_file(&path);
+    let listener = match std::fs::create_dir_all(utils::local_state_dir())
+        .and_then(|_| UnixListener::bind(&path))
+    {
+        Ok(listener) => listener,
+        Err(err) => {
+            log::warn!("failed to listen for other instances: {err}");
+            return true;
+        }
+    };
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let mut request = String::new();
+            if let Err(err) = BufReader::new(&stream).read_line(&mut request) {
+                log::warn!("failed to read a request: {err}");
+                continue;
+            }
+            let reply = handle(request.trim());
+            if let Err(err) = (&stream).write_all(reply.as_bytes()) {
+                log::warn!("failed to answer a request: {err}");
+            }
+        }
+    });
+    true
+}
+
+/// Sends a one line request to the running instance and waits for the reply
+pub fn send(request: &str) -> io::Result<String> {
+    let mut stream = UnixStream::connect(socket_path())?;
+    stream.write_all(format!("{request}\n").as_bytes())?;
+    stream.shutdown(Shutdown::Write)?;
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply)?;
+    Ok(reply)
+}
+
+/// Focus requests from later launches, meant for `Subscription::run`
+pub fn focus_requests() -> impl Stream<Item = Msg> {
+    let receiver = FOCUS_REQUESTS.lock().unwrap().take();
+    stream::iter(receiver).flatten().map(|()| Msg::FocusWindow)
+}
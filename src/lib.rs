@@ -0,0 +1,10 @@
+//! The countdown itself, free of any GUI so the app, the daemon and other
+//! programs all time things the same way. The UI strings live here too, timers
+//! name themselves in the current language.
+//!
+//! A [`timer::Timer`] is edited through its `hours`/`minutes`/`seconds` fields,
+//! started with [`timer::Timer::start`] and advanced with [`timer::Timer::tick`],
+//! which reports the moment it finishes.
+
+pub mod i18n;
+pub mod timer;
@@ -0,0 +1,68 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::utils;
+
+/// Appends our own records to `oxyclock.log` in the state dir so GUI users have
+/// something to attach to bug reports. Debug builds mirror everything to stderr.
+struct Logger {
+    file: Option<Mutex<File>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target().starts_with("oxyclock") && metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line = format!("[{timestamp}] {} {}\n", record.level(), record.args());
+
+        if cfg!(debug_assertions) {
+            eprint!("{line}");
+        }
+        if let Some(Ok(mut file)) = self.file.as_ref().map(|file| file.lock()) {
+            let _ = file.write_all(line.as_bytes());
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(Ok(mut file)) = self.file.as_ref().map(|file| file.lock()) {
+            let _ = file.flush();
+        }
+    }
+}
+
+pub fn init() {
+    let dir = utils::local_state_dir();
+    let file = std::fs::create_dir_all(&dir)
+        .and_then(|_| {
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(dir.join("oxyclock.log"))
+        })
+        .ok()
+        .map(Mutex::new);
+
+    let level = if cfg!(debug_assertions) {
+        LevelFilter::Debug
+    } else {
+        LevelFilter::Info
+    };
+    if log::set_boxed_logger(Box::new(Logger { file })).is_ok() {
+        log::set_max_level(level);
+    }
+}
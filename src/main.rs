@@ -1,54 +1,264 @@
+use chrono::Timelike;
 use components::{
-    custom_button, delete_icon, pause_icon, reset_icon, save_icon, scrollable_content, start_icon,
-    time_container, top_bar, CustomButtonType,
+    action_label, announce_input, command_palette, created_text, custom_button, dashboard_page,
+    delete_icon, directed_row, done_badge, duplicate_name_badge, elapsed_text, ends_at_text,
+    error_text, icon_input, icon_text, input_id, keypad, keypad_value, labelled, laps_list,
+    leading, mini_page, muted_icon, note_editor, palette_input_id, pause_icon, percentage_text,
+    pin_icon, quick_add_bar, reset_icon, resume_prompt, running_bar, save_icon, schedule_editor,
+    scrollable_content, selection_bar, settings_page, start_icon, templates_page, time_container,
+    timer_options, timers_scroll_id, toast, top_bar, trailing, warning_banner, webhook_input,
+    CustomButtonType, Field, KeypadKey, TimeMode,
 };
+use i18n::{t, t_fmt};
 use iced::{
     alignment::Horizontal,
-    theme,
-    widget::{center, column, container, horizontal_space, row},
-    window, Alignment, Border, Element, Length, Shadow, Subscription, Task, Theme,
+    clipboard, keyboard, theme,
+    widget::{
+        canvas, center, checkbox, column, container, horizontal_space, row, scrollable, stack,
+        text, text_editor, text_input, Column, Scrollable,
+    },
+    window, Alignment, Border, Element, Length, Shadow, Size, Subscription, Task, Theme,
+};
+use std::io::{self, BufReader, BufWriter, Write};
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    fs::File,
+    path::{Path, PathBuf},
+    time::{Duration, Instant, SystemTime},
 };
-use std::io::{BufReader, BufWriter, Write};
-use std::{fs::File, time::Duration};
 use uuid::Uuid;
 
+use oxyclock::{i18n, timer};
+
+mod cli;
 mod components;
+mod config;
+mod csv;
 mod custom_theme;
-mod timer;
+mod daemon;
+mod dial;
+mod history;
+mod instance;
+mod logger;
+mod mqtt;
+mod stats;
+mod templates;
 mod utils;
+mod webhook;
+
+/// Fastest a running timer wakes up, however close it is to finishing
+const MIN_TICK: Duration = Duration::from_millis(100);
+/// One fade in and out of the highlight on cards that went off
+const PULSE_PERIOD_MS: u128 = 1200;
+/// How long a toast stays on screen before the next one in the queue
+const TOAST_DURATION: Duration = Duration::from_secs(4);
 
 fn main() -> iced::Result {
+    logger::init();
+    let config = config::Config::load();
+    i18n::init(config.locale.as_deref(), config.rtl);
+    utils::init_state_dir(config.state_dir.clone());
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("--list" | "list") => {
+            cli::list(&config.profile);
+            return Ok(());
+        }
+        Some("--daemon") => {
+            daemon::run(config);
+            return Ok(());
+        }
+        Some("--watch") => {
+            let format = match args.get(1..) {
+                Some([flag, value]) if flag == "--format" => cli::WatchFormat::parse(value),
+                Some([]) => Some(cli::WatchFormat::Hms),
+                _ => None,
+            };
+            match format {
+                Some(format) => cli::watch(&config.profile, format),
+                None => eprintln!("usage: oxyclock --watch [--format hms|mmss]"),
+            }
+            return Ok(());
+        }
+        Some("status" | "start" | "pause" | "reset") => {
+            cli::control(&args.join(" "));
+            return Ok(());
+        }
+        _ => {}
+    }
+    // A second launch only brings the running instance to the front, two
+    // processes writing state.json would clobber each other
+    if !instance::acquire() {
+        return Ok(());
+    }
+
     iced::daemon(Oxyclock::title, Oxyclock::update, Oxyclock::view)
         .settings(iced::Settings {
             id: Some("oxyclock".to_string()),
             ..iced::Settings::default()
         })
         .theme(Oxyclock::theme)
+        .scale_factor(Oxyclock::scale_factor)
         .subscription(Oxyclock::subscription)
         .font(include_bytes!("../resources/fonts/icons-font.ttf").as_slice())
         .run_with(|| {
-            let (_, task) = window::open(window::Settings::default());
             let (state, _) = Oxyclock::load_state();
+            let (_, task) = window::open(state.window_settings());
 
             (state, task.map(Msg::WindowOpened))
         })
 }
 
+/// Actions offered by the command palette
+#[derive(Debug, Clone)]
+enum Command {
+    AddTimer,
+    StartAll,
+    NextTheme,
+    Open(Page),
+    JumpTo(Uuid),
+}
+
 #[derive(Debug, Clone)]
 enum Msg {
     WindowOpened(window::Id),
+    WindowResized(Size),
+    FocusWindow,
+    OpenPalette,
+    ClosePalette,
+    KeyPressed(keyboard::Key, keyboard::Modifiers),
+    /// Binds the next key combination pressed to the action
+    CaptureShortcut(config::Action),
+    ResetShortcuts,
+    PaletteQuery(String),
+    RunCommand(Command),
+    StartAll,
+    ToggleMiniMode,
     AddTimer,
+    QuickAddInput(String),
+    QuickAdd(String),
+    AddTemplate,
+    UseTemplate(Uuid),
+    DeleteTemplate(Uuid),
+    TemplateName((Uuid, String)),
+    TemplateDuration((Uuid, String)),
+    TemplateNotify((Uuid, bool)),
+    TemplateSilent((Uuid, bool)),
     SaveTimer(Uuid),
     DeleteTimer(Uuid),
     Tick(Uuid),
     Start(Uuid),
     Stop(Uuid),
+    Stopwatch((Uuid, bool)),
+    Lap(Uuid),
     Reset(Uuid),
     PlayNotification(Uuid),
     Hours(Time),
     Minutes(Time),
     Seconds(Time),
+    Digits(Time),
     Name((Uuid, String)),
+    Notify((Uuid, bool)),
+    Silent((Uuid, bool)),
+    Break((Uuid, Option<Duration>)),
+    AlarmRepeats((Uuid, u32)),
+    Note((Uuid, text_editor::Action)),
+    ToggleNote(Uuid),
+    Keypad((Uuid, KeypadKey)),
+    Dial((Uuid, Duration)),
+    KeypadField((Uuid, Field)),
+    TouchMode(bool),
+    ShowPercentage(bool),
+    Percentage(config::Percentage),
+    ShowElapsed(bool),
+    ShowCreated(bool),
+    ShowEndsAt(bool),
+    ReduceMotion(bool),
+    Animate,
+    Clock24h(bool),
+    DigitEntry(bool),
+    NumberDuplicates(bool),
+    Announce(bool),
+    RoundTo(Option<Duration>),
+    AnnounceAt((Uuid, String)),
+    ScheduleDay((Uuid, usize)),
+    ScheduleTime((Uuid, String)),
+    RequestAttention(bool),
+    PauseWhenAway(bool),
+    CheckAway,
+    Away(Option<bool>),
+    FocusNext,
+    FocusPrevious,
+    CopyTimer(Uuid),
+    TogglePin(Uuid),
+    Icon((Uuid, String)),
+    Webhook((Uuid, String)),
+    WebhookSent(Result<(), String>),
+    PasteTimer,
+    TimerPasted(Option<String>),
+    ThemeSelected(config::ThemeChoice),
+    AccentInput(String),
+    NotificationUrgency(config::Urgency),
+    NotificationTimeout(config::NotificationTimeout),
+    ViewPresetSelected(config::ViewPreset),
+    ViewPresetNameInput(String),
+    SaveViewPreset,
+    SoundPathChanged(String),
+    StartSoundPathChanged(String),
+    TickSoundPathChanged(String),
+    VolumeChanged(f32),
+    ScaleFactorChanged(f64),
+    SortSelected(config::SortOrder),
+    DailyGoalChanged(u32),
+    RingUntilDismissed(bool),
+    DefaultAlarmRepeats(u32),
+    FinalWarning(bool),
+    FinalWarningSeconds(u64),
+    FinalWarningPulse(bool),
+    TimersScrolled(scrollable::Viewport),
+    TestSound,
+    TestSoundFinished(Result<(), utils::SoundError>),
+    NotificationSoundFinished(Result<(), utils::SoundError>),
+    DismissAudioWarning,
+    CheckSoundPath,
+    DismissSoundWarning,
+    DeleteFocused,
+    ScrollTo(Uuid),
+    ToggleSelecting,
+    ToggleSelected(Uuid),
+    StartSelected,
+    StopSelected,
+    DeleteSelected,
+    ProfileSelected(String),
+    NewProfileInput(String),
+    AddProfile,
+    UndoDelete,
+    DismissUndo,
+    RetrySave,
+    DismissSaveError,
+    ShowToast(String),
+    ToastTick(Instant),
+    DetectSystemTheme,
+    Navigate(Page),
+    ImportPathChanged(String),
+    ImportCsv(PathBuf),
+    ExportPathChanged(String),
+    ExportCsv(PathBuf),
+    FlushState,
+    AlarmDone(Uuid),
+    HoldFinished(bool),
+    AfterFinish(config::AfterFinish),
+    CloseRequested,
+    ResumeSession,
+    DiscardSession,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Page {
+    Timers,
+    Settings,
+    Dashboard,
+    Templates,
 }
 
 #[derive(Debug, Clone, Hash)]
@@ -59,12 +269,137 @@ struct Time {
 
 struct Oxyclock {
     timers: Vec<timer::Timer>,
+    page: Page,
+    focused: Option<(Uuid, Field)>,
+    config: config::Config,
+    system_dark: bool,
+    accent_input: String,
+    /// Name typed for saving the current appearance as a view preset
+    new_view_preset: String,
+    sound_error: Option<String>,
+    audio_unavailable: bool,
+    audio_warning: bool,
+    save_error: Option<String>,
+    import_path: String,
+    import_result: Option<String>,
+    export_path: String,
+    export_result: Option<String>,
+    history: Vec<history::Record>,
+    summary: Option<stats::Summary>,
+    /// Edits not yet written to disk, flushed by `Msg::FlushState`
+    dirty: bool,
+    /// Same for settings typed into a text field
+    config_dirty: bool,
+    /// Same for template names and durations
+    templates_dirty: bool,
+    /// Editors of the notes that are currently expanded
+    notes: HashMap<Uuid, text_editor::Content>,
+    /// Restored when coming back to the timers page, which rebuilds the list
+    timers_scroll: scrollable::AbsoluteOffset,
+    quick_add: String,
+    /// Query of the command palette, `None` while it's closed
+    palette: Option<String>,
+    /// Action waiting for a key combination on the settings page
+    capturing: Option<config::Action>,
+    shortcut_error: Option<String>,
+    /// Messages waiting to be shown at the bottom of the window, the first one is
+    /// on screen until `toast_expires`
+    toasts: VecDeque<String>,
+    /// When the toast on screen goes away, set once it's shown so the ones queued
+    /// behind it get their full time too
+    toast_expires: Option<Instant>,
+    /// Timers found running at launch, held stopped until resumed or discarded
+    interrupted: Vec<Uuid>,
+    /// Alarms ringing until dismissed, stopped with the timer or when it's deleted
+    alarms: HashMap<Uuid, utils::AlarmHandle>,
+    templates: Vec<templates::Template>,
+    /// Duration text being typed on the templates page, kept until it parses
+    template_durations: HashMap<Uuid, String>,
+    /// Announcement times being typed into a card, kept until they parse
+    announce_inputs: HashMap<Uuid, String>,
+    /// Schedule times being typed that don't parse yet
+    schedule_inputs: HashMap<Uuid, String>,
+    quick_add_error: Option<String>,
+    window: Option<window::Id>,
+    /// Size to come back to when leaving mini mode
+    full_size: Size,
+    mqtt: Option<mqtt::Publisher>,
+    /// Strength of the pulse on cards that went off, from 0 to 1
+    pulse: f32,
+    /// Timers of the last deletion with their positions, in ascending order, until
+    /// they're restored or dismissed
+    deleted: Vec<(usize, timer::Timer)>,
+    /// Timers paused because the session locked or went idle, resumed once it's back
+    away_paused: Vec<Uuid>,
+    /// Whether the cards show checkboxes for bulk actions
+    selecting: bool,
+    selected: HashSet<Uuid>,
+    profiles: Vec<String>,
+    /// Name typed into the new profile field on the settings page
+    new_profile: String,
+    /// Banner about an alarm sound that can't be played
+    sound_warning: Option<String>,
+    /// Sound the banner was shown for, so it only comes up once per file
+    warned_sound: Option<PathBuf>,
 }
 
 impl Default for Oxyclock {
     fn default() -> Self {
+        let config = config::Config::load();
         Oxyclock {
             timers: vec![timer::Timer::default()],
+            page: Page::Timers,
+            focused: None,
+            system_dark: detect_system_dark(),
+            accent_input: config.accent.clone().unwrap_or_default(),
+            new_view_preset: String::new(),
+            sound_error: None,
+            audio_unavailable: false,
+            audio_warning: false,
+            save_error: None,
+            import_path: String::new(),
+            import_result: None,
+            export_path: String::new(),
+            export_result: None,
+            history: history::load(),
+            summary: None,
+            dirty: false,
+            config_dirty: false,
+            templates_dirty: false,
+            notes: HashMap::new(),
+            timers_scroll: scrollable::AbsoluteOffset::default(),
+            quick_add: String::new(),
+            palette: None,
+            capturing: None,
+            shortcut_error: None,
+            toasts: VecDeque::new(),
+            toast_expires: None,
+            interrupted: Vec::new(),
+            alarms: HashMap::new(),
+            templates: templates::load(),
+            template_durations: HashMap::new(),
+            announce_inputs: HashMap::new(),
+            schedule_inputs: HashMap::new(),
+            quick_add_error: None,
+            window: None,
+            full_size: window::Settings::default().size,
+            mqtt: config.mqtt.as_ref().and_then(mqtt::Publisher::connect),
+            pulse: 0.0,
+            deleted: Vec::new(),
+            away_paused: Vec::new(),
+            selecting: false,
+            selected: HashSet::new(),
+            profiles: {
+                let mut profiles = utils::profiles();
+                if !profiles.contains(&config.profile) {
+                    profiles.push(config.profile.clone());
+                }
+                profiles
+            },
+            new_profile: String::new(),
+            sound_warning: None,
+            warned_sound: None,
+            config,
         }
     }
 }
@@ -74,42 +409,247 @@ impl Oxyclock {
         "Oxyclock".to_string()
     }
     fn view(&self, _window_id: window::Id) -> Element<'_, Msg> {
+        if self.config.mini_mode {
+            let running = self
+                .timers
+                .iter()
+                .enumerate()
+                .filter(|(_, timer)| timer.state == timer::State::Running)
+                .map(|(position, timer)| {
+                    (timer.display_name(position), utils::format_hms(timer.time))
+                })
+                .collect();
+            return mini_page(running).into();
+        }
+
+        let page = match self.page {
+            Page::Timers => column![
+                top_bar(self.config.sort, &self.profiles, &self.config.profile),
+                if self.selecting {
+                    selection_bar(self.selected.len())
+                } else {
+                    quick_add_bar(&self.quick_add, self.quick_add_error.as_deref())
+                },
+                self.banners(),
+            ]
+            .push_maybe(self.running_bar())
+            .push(
+                scrollable_content(self.timers_view())
+                    .id(timers_scroll_id())
+                    .on_scroll(Msg::TimersScrolled),
+            ),
+            Page::Settings => column![
+                self.banners(),
+                scrollable_content(settings_page(
+                    &self.config,
+                    &self.accent_input,
+                    self.sound_error.as_deref(),
+                    &self.import_path,
+                    self.import_result.as_deref(),
+                    &self.export_path,
+                    self.export_result.as_deref(),
+                    &self.new_profile,
+                    &self.new_view_preset,
+                    self.capturing,
+                    self.shortcut_error.as_deref()
+                ))
+            ],
+            Page::Dashboard => column![
+                self.banners(),
+                scrollable_content(dashboard_page(
+                    self.summary.as_ref(),
+                    self.config.daily_goal
+                ))
+            ],
+            Page::Templates => column![
+                self.banners(),
+                scrollable_content(templates_page(&self.templates, &self.template_durations))
+            ],
+        };
+
+        let content = container(center(
+            page.push(horizontal_space().height(Length::FillPortion(1)))
+                .spacing(10),
+        ))
+        .height(Length::Fill)
+        .align_y(Alignment::End);
+
+        let content: Element<'_, Msg> = if !self.interrupted.is_empty() {
+            stack![content, resume_prompt(self.interrupted.len())].into()
+        } else {
+            match &self.palette {
+                Some(query) => stack![content, command_palette(query, self.commands(query))].into(),
+                None => content.into(),
+            }
+        };
+        match self.toasts.front() {
+            Some(message) => stack![content, toast(message, self.toasts.len() - 1)].into(),
+            None => content,
+        }
+    }
+
+    fn banners(&self) -> Column<'_, Msg> {
+        column![]
+            .push_maybe(self.save_error.as_deref().map(|err| {
+                warning_banner(
+                    err,
+                    Some((t("retry"), Msg::RetrySave)),
+                    Msg::DismissSaveError,
+                )
+            }))
+            .push_maybe(
+                self.sound_warning
+                    .as_deref()
+                    .map(|warning| warning_banner(warning, None, Msg::DismissSoundWarning)),
+            )
+            .push_maybe((!self.deleted.is_empty()).then(|| {
+                let message = match self.deleted.as_slice() {
+                    [(index, timer)] => {
+                        t_fmt("timer_deleted", &[("name", &timer.display_name(*index))])
+                    }
+                    deleted => t_fmt("timers_deleted", &[("count", &deleted.len().to_string())]),
+                };
+                warning_banner(
+                    &message,
+                    Some((t("undo"), Msg::UndoDelete)),
+                    Msg::DismissUndo,
+                )
+            }))
+            .push_maybe(
+                self.audio_warning
+                    .then(|| warning_banner(t("no_audio_device"), None, Msg::DismissAudioWarning)),
+            )
+            .spacing(10)
+            .padding([0, 10])
+    }
+
+    fn timers_view(&self) -> Column<'_, Msg> {
         let mut timers_container = column![].width(Length::Fill).align_x(Horizontal::Center);
-        for timer in self.timers.iter() {
+        for (position, timer) in self.sorted_timers() {
             let started = timer.state == timer::State::Running;
+            let stopwatch = timer.mode == timer::Mode::Stopwatch;
+            let highlight = is_alarmed(timer).then_some(if self.config.reduce_motion {
+                1.0
+            } else {
+                self.pulse
+            });
+            let label = timer.display_name(position);
 
             let buttons = if started {
                 container(
-                    custom_button(pause_icon(), CustomButtonType::Primary, None, None)
+                    row![
+                        labelled(
+                            custom_button(pause_icon(), CustomButtonType::Primary, None, None)
+                                .on_press(Msg::Stop(timer.id)),
+                            // Stopping a stopwatch records it, pausing a countdown doesn't
+                            t_fmt(
+                                if stopwatch {
+                                    "stop_stopwatch"
+                                } else {
+                                    "pause_timer"
+                                },
+                                &[("name", &label)],
+                            ),
+                        ),
+                        labelled(
+                            custom_button(reset_icon(), CustomButtonType::Secondary, None, None)
+                                .on_press(Msg::Reset(timer.id)),
+                            t_fmt("cancel_timer", &[("name", &label)]),
+                        ),
+                    ]
+                    .push_maybe(stopwatch.then(|| {
+                        custom_button(
+                            text(t("lap")).size(12),
+                            CustomButtonType::Secondary,
+                            Some(60f32),
+                            None,
+                        )
+                        .on_press(Msg::Lap(timer.id))
+                    }))
+                    .spacing(10)
+                    .align_y(Alignment::Center),
+                )
+            } else if self.alarms.contains_key(&timer.id) {
+                container(
+                    custom_button(
+                        text(t("stop_alarm")).size(12),
+                        CustomButtonType::Primary,
+                        Some(100f32),
+                        None,
+                    )
+                    .on_press(Msg::Stop(timer.id)),
+                )
+            } else if timer.state == timer::State::Finished {
+                container(
+                    row![
+                        done_badge(),
+                        custom_button(
+                            text(t("dismiss")).size(12),
+                            CustomButtonType::Secondary,
+                            Some(80f32),
+                            None,
+                        )
                         .on_press(Msg::Stop(timer.id)),
+                    ]
+                    .spacing(10)
+                    .align_y(Alignment::Center),
                 )
             } else {
                 container(
                     row![
-                        custom_button(reset_icon(), CustomButtonType::Secondary, None, None)
-                            .on_press(Msg::Reset(timer.id)),
-                        custom_button(start_icon(), CustomButtonType::Primary, None, None)
-                            .on_press(Msg::Start(timer.id)),
+                        labelled(
+                            custom_button(reset_icon(), CustomButtonType::Secondary, None, None)
+                                .on_press(Msg::Reset(timer.id)),
+                            t_fmt("reset_timer", &[("name", &label)]),
+                        ),
+                        labelled(
+                            // Greyed out until the fields hold something to count down
+                            custom_button(start_icon(), CustomButtonType::Primary, None, None)
+                                .on_press_maybe(
+                                    timer
+                                        .can_start(self.config.max_duration)
+                                        .then_some(Msg::Start(timer.id)),
+                                ),
+                            t_fmt("start_timer", &[("name", &label)]),
+                        ),
                     ]
                     .spacing(10),
                 )
             };
 
-            let time_container = if started {
+            let time_container = if started || stopwatch {
                 let (hours, minutes, seconds) = timer.time_to_hms_string();
-                time_container(timer.id, &timer.name, hours, minutes, seconds, true)
+                let mode = if !started {
+                    TimeMode::Fixed
+                } else if self.in_final_seconds(timer) {
+                    TimeMode::Ending
+                } else {
+                    TimeMode::Running
+                };
+                time_container(
+                    timer.id,
+                    &timer.name,
+                    &label,
+                    hours,
+                    minutes,
+                    seconds,
+                    mode,
+                    self.config.digit_entry,
+                )
             } else {
                 time_container(
                     timer.id,
                     &timer.name,
+                    &label,
                     timer.hours.clone(),
                     timer.minutes.clone(),
                     timer.seconds.clone(),
-                    false,
+                    TimeMode::Editing,
+                    self.config.digit_entry,
                 )
             };
 
-            let delete_button = container(
+            let delete_button = container(labelled(
                 custom_button(
                     delete_icon().size(14f32),
                     CustomButtonType::Secondary,
@@ -117,43 +657,210 @@ impl Oxyclock {
                     Some(30f32),
                 )
                 .on_press(Msg::DeleteTimer(timer.id)),
-            )
-            .align_left(Length::Fill);
+                t_fmt("delete_timer", &[("name", &label)]),
+            ))
+            .width(Length::Fill)
+            .align_x(leading());
 
             let save_button = container(
-                custom_button(
-                    save_icon().size(14f32),
-                    CustomButtonType::Success,
-                    Some(30f32),
-                    Some(30f32),
-                )
-                .on_press(Msg::SaveTimer(timer.id)),
+                row![
+                    labelled(
+                        custom_button(
+                            pin_icon(timer.pinned),
+                            CustomButtonType::Secondary,
+                            Some(30f32),
+                            Some(30f32),
+                        )
+                        .on_press(Msg::TogglePin(timer.id)),
+                        if timer.pinned {
+                            t_fmt("unpin_timer", &[("name", &label)])
+                        } else {
+                            t_fmt("pin_timer", &[("name", &label)])
+                        },
+                    ),
+                    custom_button(
+                        text(t("copy")).size(12),
+                        CustomButtonType::Secondary,
+                        Some(50f32),
+                        Some(30f32),
+                    )
+                    .on_press(Msg::CopyTimer(timer.id)),
+                    labelled(
+                        custom_button(
+                            save_icon().size(14f32),
+                            CustomButtonType::Success,
+                            Some(30f32),
+                            Some(30f32),
+                        )
+                        .on_press(Msg::SaveTimer(timer.id)),
+                        t_fmt("save_timer", &[("name", &label)]),
+                    ),
+                ]
+                .spacing(10),
             )
-            .align_right(Length::Fill);
+            .width(Length::Fill)
+            .align_x(trailing());
 
             let timer_container = container(column![
                 container(
-                    column![
-                        if started {
-                            row![].height(30)
-                        } else {
-                            row![delete_button, save_button].width(Length::Fill)
-                        },
-                        column![time_container, buttons]
-                            .spacing(20)
-                            .align_x(Alignment::Center)
-                    ]
-                    .align_x(Alignment::Center)
+                    column![]
+                        .push_maybe(self.selecting.then(|| {
+                            checkbox(t("select"), self.selected.contains(&timer.id))
+                                .on_toggle(move |_| Msg::ToggleSelected(timer.id))
+                                .size(16)
+                                .text_size(12)
+                        }))
+                        // Running cards drop the delete/save row altogether
+                        .push_maybe((!started).then(|| {
+                            directed_row(vec![delete_button.into(), save_button.into()])
+                                .width(Length::Fill)
+                        }))
+                        .push(
+                            column![]
+                                .push_maybe(timer.icon.as_deref().and_then(icon_text))
+                                .push(time_container)
+                                .push_maybe(
+                                    (started && self.config.show_elapsed)
+                                        .then(|| elapsed_text(timer.elapsed))
+                                )
+                                // Worked out on every redraw, so it follows clock changes
+                                .push_maybe(
+                                    (started && !stopwatch && self.config.show_ends_at).then(
+                                        || {
+                                            let now = SystemTime::now();
+                                            ends_at_text(&utils::format_clock(
+                                                now + timer.time,
+                                                now,
+                                                self.config.clock_24h,
+                                            ))
+                                        }
+                                    )
+                                )
+                                .push_maybe(
+                                    self.has_duplicate_name(timer).then(duplicate_name_badge)
+                                )
+                                .push_maybe(
+                                    (!started && self.config.show_created)
+                                        .then(|| created_text(timer.created_at))
+                                )
+                                .push_maybe((!stopwatch).then(|| self.dial(timer, started)))
+                                .push_maybe((!started).then(|| {
+                                    timer_options(
+                                        timer.id,
+                                        timer.notify,
+                                        timer.silent,
+                                        timer.after_finish,
+                                        timer.alarm_repeats.unwrap_or(self.config.alarm_repeats),
+                                        self.notes.contains_key(&timer.id),
+                                        stopwatch,
+                                    )
+                                }))
+                                .push_maybe(
+                                    self.notes
+                                        .get(&timer.id)
+                                        .map(|content| note_editor(timer.id, content))
+                                )
+                                // Scheduled timers are always running, so their days stay in reach
+                                .push_maybe((!started || is_scheduled(timer)).then(|| {
+                                    let schedule = timer.schedule.clone().unwrap_or_default();
+                                    let input = self.schedule_inputs.get(&timer.id).cloned();
+                                    schedule_editor(
+                                        timer.id,
+                                        schedule.days,
+                                        input.unwrap_or_else(|| {
+                                            format!(
+                                                "{:02}:{:02}",
+                                                schedule.at / 60,
+                                                schedule.at % 60
+                                            )
+                                        }),
+                                    )
+                                }))
+                                .push_maybe((!started).then(|| {
+                                    icon_input(timer.id, timer.icon.as_deref().unwrap_or(""))
+                                }))
+                                .push_maybe((cfg!(feature = "webhook") && !started).then(|| {
+                                    webhook_input(timer.id, timer.webhook.as_deref().unwrap_or(""))
+                                }))
+                                .push_maybe((self.config.announce && !started).then(|| {
+                                    let input = self.announce_inputs.get(&timer.id).cloned();
+                                    announce_input(
+                                        timer.id,
+                                        input.unwrap_or_else(|| {
+                                            timer
+                                                .announce_at
+                                                .iter()
+                                                .flatten()
+                                                .map(|milestone| utils::format_hms(*milestone))
+                                                .collect::<Vec<_>>()
+                                                .join(", ")
+                                        }),
+                                    )
+                                }))
+                                .push_maybe(
+                                    (!timer.laps.is_empty()).then(|| laps_list(&timer.laps))
+                                )
+                                .push_maybe(timer.silent.then(muted_icon))
+                                .push_maybe(timer.on_break.then(|| text(t("on_break")).size(12)))
+                                .push_maybe(timer.error.as_deref().map(error_text))
+                                .push_maybe(
+                                    (self.config.touch_mode && !started && !stopwatch)
+                                        .then(|| { keypad(timer.id, self.keypad_field(timer.id)) })
+                                )
+                                .push_maybe(
+                                    (started && !stopwatch && self.config.show_percentage).then(
+                                        || {
+                                            percentage_text(
+                                                timer.remaining_percentage(),
+                                                self.config.percentage,
+                                            )
+                                        }
+                                    )
+                                )
+                                .push(buttons)
+                                .spacing(20)
+                                .align_x(Alignment::Center)
+                        )
+                        .align_x(Alignment::Center)
                 )
                 .width(Length::Fill)
                 .align_x(Alignment::Center)
                 .padding(20)
-                .style(|theme: &Theme| {
+                .style(move |theme: &Theme| {
                     let palette = theme.extended_palette();
+                    // A card that went off pulses, or just stays highlighted without motion
+                    let Some(highlight) = highlight else {
+                        return container::Style {
+                            text_color: None,
+                            background: Some(
+                                custom_theme::faded(theme, palette.secondary.base.color, 0.1)
+                                    .into(),
+                            ),
+                            border: Border::default()
+                                .rounded(8)
+                                .width(if custom_theme::is_high_contrast(theme) {
+                                    1
+                                } else {
+                                    0
+                                })
+                                .color(theme.palette().text),
+                            shadow: Shadow::default(),
+                        };
+                    };
                     container::Style {
                         text_color: None,
-                        background: Some(palette.secondary.base.color.scale_alpha(0.1).into()),
-                        border: Border::default().rounded(8),
+                        background: Some(
+                            custom_theme::faded(
+                                theme,
+                                palette.primary.base.color,
+                                0.1 + 0.3 * highlight,
+                            )
+                            .into(),
+                        ),
+                        border: Border::default()
+                            .rounded(8)
+                            .width(2)
+                            .color(palette.primary.base.color),
                         shadow: Shadow::default(),
                     }
                 }),
@@ -165,164 +872,2074 @@ impl Oxyclock {
             timers_container = timers_container.push(timer_container);
         }
 
-        container(center(
-            column![
-                top_bar(),
-                scrollable_content(timers_container),
-                horizontal_space().height(Length::FillPortion(1))
-            ]
-            .spacing(10),
-        ))
-        .height(Length::Fill)
-        .align_y(Alignment::End)
-        .into()
+        timers_container
     }
 
     fn update(&mut self, msg: Msg) -> Task<Msg> {
         match msg {
-            Msg::WindowOpened(_id) => Task::none(),
+            Msg::WindowOpened(id) => {
+                self.window = Some(id);
+                Task::none()
+            }
+            Msg::OpenPalette => {
+                self.palette = Some(String::new());
+                text_input::focus(palette_input_id())
+            }
+            Msg::ClosePalette => {
+                self.palette = None;
+                self.capturing = None;
+                self.shortcut_error = None;
+                Task::none()
+            }
+            Msg::KeyPressed(key, modifiers) => {
+                let Some(name) = config::shortcut_name(&key, modifiers) else {
+                    return Task::none();
+                };
+                let shortcuts = self.config.shortcuts();
+                if let Some(action) = self.capturing {
+                    let taken = shortcuts
+                        .iter()
+                        .find(|(other, names)| *other != action && names.contains(&name));
+                    if let Some((other, _)) = taken {
+                        self.shortcut_error = Some(t_fmt(
+                            "shortcut_taken",
+                            &[("shortcut", &name), ("action", action_label(*other))],
+                        ));
+                        return Task::none();
+                    }
+                    self.config.shortcuts.insert(action, vec![name]);
+                    self.config.save();
+                    self.capturing = None;
+                    self.shortcut_error = None;
+                    return Task::none();
+                }
+
+                let action = shortcuts
+                    .into_iter()
+                    .find(|(_, names)| names.contains(&name))
+                    .map(|(action, _)| action);
+                match action {
+                    Some(config::Action::OpenPalette) => Task::done(Msg::OpenPalette),
+                    Some(config::Action::AddTimer) => Task::done(Msg::AddTimer),
+                    Some(config::Action::UndoDelete) => Task::done(Msg::UndoDelete),
+                    Some(config::Action::DeleteFocused) => Task::done(Msg::DeleteFocused),
+                    None => Task::none(),
+                }
+            }
+            Msg::CaptureShortcut(action) => {
+                self.capturing = Some(action);
+                self.shortcut_error = None;
+                Task::none()
+            }
+            Msg::ResetShortcuts => {
+                self.config.shortcuts.clear();
+                self.config.save();
+                self.capturing = None;
+                self.shortcut_error = None;
+                Task::done(Msg::ShowToast(t("shortcuts_reset").to_string()))
+            }
+            Msg::PaletteQuery(query) => {
+                self.palette = Some(query);
+                Task::none()
+            }
+            Msg::RunCommand(command) => {
+                self.palette = None;
+                match command {
+                    Command::AddTimer => Task::done(Msg::AddTimer),
+                    Command::StartAll => Task::done(Msg::StartAll),
+                    Command::NextTheme => {
+                        let current = config::ThemeChoice::ALL
+                            .iter()
+                            .position(|theme| *theme == self.config.theme)
+                            .unwrap_or(0);
+                        let next = config::ThemeChoice::ALL
+                            [(current + 1) % config::ThemeChoice::ALL.len()];
+                        Task::done(Msg::ThemeSelected(next))
+                    }
+                    Command::Open(page) => Task::done(Msg::Navigate(page)),
+                    Command::JumpTo(id) => {
+                        self.page = Page::Timers;
+                        text_input::focus(input_id(id, Field::Hours))
+                    }
+                }
+            }
+            Msg::StartAll => Task::batch(
+                self.timers
+                    .iter()
+                    .filter(|timer| {
+                        timer.state == timer::State::Stopped
+                            && timer.can_start(self.config.max_duration)
+                    })
+                    .map(|timer| Task::done(Msg::Start(timer.id))),
+            ),
+            Msg::FocusWindow => self.window.map_or_else(Task::none, window::gain_focus),
+            Msg::WindowResized(size) => {
+                if !self.config.mini_mode {
+                    self.full_size = size;
+                }
+                Task::none()
+            }
+            Msg::ToggleMiniMode => {
+                self.config.mini_mode = !self.config.mini_mode;
+                self.config.save();
+                // The window level and minimum size are fixed at creation, so
+                // swap the window for one set up for the new mode
+                let (_, open) = window::open(self.window_settings());
+                let close = self.window.take().map_or_else(Task::none, window::close);
+                Task::batch([close, open.map(Msg::WindowOpened)])
+            }
             Msg::AddTimer => {
-                self.timers.push(timer::Timer::new(uuid::Uuid::new_v4()));
-                self.save_state(&self.timers);
+                let mut timer = timer::Timer::new(uuid::Uuid::new_v4());
+                if self.config.remember_last_duration {
+                    timer.set_hms(self.config.last_duration);
+                }
+                let id = timer.id;
+                self.timers.push(timer);
+                self.save_timers();
+
+                // The card isn't necessarily last once sorted, so scroll to where it landed
+                Task::batch([
+                    self.scroll_to(id),
+                    text_input::focus(input_id(id, Field::Hours)),
+                ])
+            }
+            Msg::ScrollTo(id) => self.scroll_to(id),
+            Msg::QuickAddInput(input) => {
+                self.quick_add = input;
+                self.quick_add_error = None;
+                Task::none()
+            }
+            Msg::QuickAdd(input) => {
+                let Some(quick_add) = utils::parse_quick_add(&input) else {
+                    self.quick_add_error =
+                        Some(t_fmt("quick_add_no_duration", &[("input", &input)]));
+                    return Task::none();
+                };
+                let max = self.config.max_duration.min(timer::MAX_DURATION);
+                if quick_add.duration > max {
+                    self.quick_add_error = Some(t_fmt(
+                        "longer_than_max",
+                        &[
+                            ("duration", &utils::format_hms(quick_add.duration)),
+                            ("max", &utils::format_hms(max)),
+                        ],
+                    ));
+                    return Task::none();
+                }
+
+                let mut timer = timer::Timer::new(uuid::Uuid::new_v4());
+                timer.name = quick_add.name;
+                timer.set_hms(quick_add.duration);
+                let id = timer.id;
+                self.timers.push(timer);
+                self.save_timers();
+                self.quick_add.clear();
+
+                if quick_add.start {
+                    Task::done(Msg::Start(id))
+                } else {
+                    Task::none()
+                }
+            }
+            Msg::AddTemplate => {
+                self.templates.push(templates::Template::default());
+                self.save_templates();
+                Task::none()
+            }
+            Msg::UseTemplate(id) => {
+                if let Some(template) = self.templates.iter().find(|t| t.id == id) {
+                    self.timers.push(template.instantiate());
+                    self.save_timers();
+                }
+                self.page = Page::Timers;
+                Task::none()
+            }
+            Msg::DeleteTemplate(id) => {
+                self.templates.retain(|t| t.id != id);
+                self.template_durations.remove(&id);
+                self.save_templates();
+                Task::none()
+            }
+            Msg::TemplateName((id, name)) => {
+                if let Some(template) = self.templates.iter_mut().find(|t| t.id == id) {
+                    template.name = name;
+                    self.templates_dirty = true;
+                }
+                Task::none()
+            }
+            Msg::TemplateDuration((id, input)) => {
+                if let Some(template) = self.templates.iter_mut().find(|t| t.id == id) {
+                    if let Some(duration) = utils::parse_duration(&input) {
+                        template.duration = duration;
+                        self.templates_dirty = true;
+                    }
+                    self.template_durations.insert(id, input);
+                }
+                Task::none()
+            }
+            Msg::TemplateNotify((id, notify)) => {
+                if let Some(template) = self.templates.iter_mut().find(|t| t.id == id) {
+                    template.notify = notify;
+                    self.save_templates();
+                }
+                Task::none()
+            }
+            Msg::TemplateSilent((id, silent)) => {
+                if let Some(template) = self.templates.iter_mut().find(|t| t.id == id) {
+                    template.silent = silent;
+                    self.save_templates();
+                }
                 Task::none()
             }
             Msg::SaveTimer(id) => {
-                let (index, timer) = self
-                    .timers
-                    .iter()
-                    .enumerate()
-                    .find(|(_, t)| t.id == id)
-                    .unwrap();
+                self.number_duplicate(id);
+                let timer = self.timers.iter().find(|t| t.id == id).unwrap();
 
-                let (mut state, task) = Oxyclock::load_state();
-                state.timers[index] = timer.clone();
-                self.save_state(&state.timers);
+                let mut saved_timers = load_timers(&self.config.profile);
+                merge_timer(&mut saved_timers, timer);
+                let result = self.save_state(&saved_timers);
+                let saved = result.is_ok();
+                self.record_save_result(result);
 
-                task
+                if saved {
+                    Task::done(Msg::ShowToast(t("timer_saved").to_string()))
+                } else {
+                    Task::none()
+                }
+            }
+            Msg::ProfileSelected(profile) => {
+                if profile == self.config.profile {
+                    return Task::none();
+                }
+                self.save_timers();
+                for (_, alarm) in self.alarms.drain() {
+                    alarm.stop();
+                }
+                self.config.profile = profile;
+                self.config.save();
+                self.timers = load_timers(&self.config.profile);
+                self.seed_timer();
+                self.focused = None;
+                self.deleted.clear();
+                self.selected.clear();
+                self.notes.clear();
+                Task::done(Msg::ShowToast(t_fmt(
+                    "profile_switched",
+                    &[("name", &self.config.profile)],
+                )))
+            }
+            Msg::NewProfileInput(name) => {
+                self.new_profile = name;
+                Task::none()
+            }
+            Msg::AddProfile => {
+                let profile = utils::sanitize_profile(&self.new_profile);
+                if profile.is_empty() {
+                    return Task::none();
+                }
+                self.new_profile.clear();
+                if !self.profiles.contains(&profile) {
+                    self.profiles.push(profile.clone());
+                }
+                Task::done(Msg::ProfileSelected(profile))
             }
             Msg::DeleteTimer(id) => {
-                let index = self.timers.iter().position(|t| t.id == id).unwrap();
-                self.timers.remove(index);
-                self.save_state(&self.timers);
+                self.delete_timers(&[id]);
+                Task::none()
+            }
+            Msg::DeleteFocused => match self.focused {
+                Some((id, _))
+                    if self.page == Page::Timers
+                        && self.palette.is_none()
+                        && !self.config.mini_mode
+                        && self.timers.iter().any(|t| t.id == id) =>
+                {
+                    Task::done(Msg::DeleteTimer(id))
+                }
+                _ => Task::none(),
+            },
+            Msg::UndoDelete => {
+                if !self.deleted.is_empty() {
+                    // Ascending, so every timer lands back where it was
+                    for (index, timer) in std::mem::take(&mut self.deleted) {
+                        self.timers.insert(index.min(self.timers.len()), timer);
+                    }
+                    self.save_timers();
+                }
+                Task::none()
+            }
+            Msg::DismissUndo => {
+                self.deleted.clear();
+                Task::none()
+            }
+            Msg::ToggleSelecting => {
+                self.selecting = !self.selecting;
+                self.selected.clear();
+                Task::none()
+            }
+            Msg::ToggleSelected(id) => {
+                if !self.selected.remove(&id) {
+                    self.selected.insert(id);
+                }
+                Task::none()
+            }
+            Msg::StartSelected => Task::batch(
+                self.timers
+                    .iter()
+                    .filter(|timer| {
+                        self.selected.contains(&timer.id)
+                            && timer.state == timer::State::Stopped
+                            && timer.can_start(self.config.max_duration)
+                    })
+                    .map(|timer| Task::done(Msg::Start(timer.id))),
+            ),
+            Msg::StopSelected => Task::batch(
+                self.timers
+                    .iter()
+                    .filter(|timer| {
+                        self.selected.contains(&timer.id) && timer.state != timer::State::Stopped
+                    })
+                    .map(|timer| Task::done(Msg::Stop(timer.id))),
+            ),
+            Msg::DeleteSelected => {
+                let ids: Vec<Uuid> = self.selected.drain().collect();
+                self.delete_timers(&ids);
                 Task::none()
             }
             Msg::Start(id) => {
+                self.number_duplicate(id);
                 let timer = self.timers.iter_mut().find(|x| x.id == id).unwrap();
-                let duration = timer.get_duration();
-                if let Ok(duration) = duration {
-                    timer.state = timer::State::Running;
-                    timer.time = duration;
-                    timer.elapsed = Duration::from_secs(0);
+                // Only fresh runs, a paused timer resumes with the exact time it had left
+                if let Some(step) = self.config.round_to.filter(|_| !timer.is_paused()) {
+                    if let Ok(duration) = timer.get_duration(self.config.max_duration) {
+                        timer.set_hms(timer::round_to(duration, step));
+                    }
                 }
-                Task::none()
+                match timer.start(self.config.max_duration) {
+                    Ok(duration) if timer.mode == timer::Mode::Countdown => {
+                        self.config.last_duration = duration;
+                        self.config.save();
+                    }
+                    Ok(_) => {}
+                    Err(timer::StartError::NoDuration) => {
+                        timer.error = Some(t("set_duration_first").to_string());
+                        return Task::none();
+                    }
+                    Err(err) => {
+                        timer.error =
+                            Some(t_fmt("invalid_duration", &[("error", &err.to_string())]));
+                        return Task::none();
+                    }
+                }
+                let silent = timer.silent;
+                // `--watch` and `--list` read the file, they'd show it as still stopped
+                self.save_timers();
+
+                // Unlike the alarm there's no bundled fallback, no file means no sound
+                let sound = match self.config.start_sound.clone().filter(|path| path.exists()) {
+                    Some(path) if !self.audio_unavailable && !silent => Task::perform(
+                        utils::play_notification_sound_in_background(
+                            Some(path),
+                            self.config.volume,
+                            utils::Repeat::Times(1),
+                        ),
+                        Msg::NotificationSoundFinished,
+                    ),
+                    _ => Task::none(),
+                };
+                self.publish(id, mqtt::Event::Started);
+                sound
             }
             Msg::Stop(id) => {
+                let mut task = Task::none();
+                if let Some(alarm) = self.alarms.remove(&id) {
+                    alarm.stop();
+                    if self.alarms.is_empty() {
+                        task = self.request_attention(false);
+                    }
+                }
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
-                timer.state = timer::State::Stopped;
-                timer.update_elapsed_hms();
+                let was_running = timer.state == timer::State::Running;
+                let went_off = is_alarmed(timer);
+                timer.pause();
+                // Once its alarm is over a scheduled timer waits for the next occurrence
+                if went_off && timer.arm_schedule(chrono::Local::now()) {
+                    self.save_timers();
+                    return task;
+                }
+                if went_off {
+                    if self.config.after_finish.apply(timer) {
+                        // Goes through the usual deletion, so it can still be undone
+                        self.delete_timers(&[id]);
+                    } else {
+                        self.save_timers();
+                    }
+                    return task;
+                }
+                // A stopwatch never finishes, stopping it is what ends a run
+                let stopwatch = timer.mode == timer::Mode::Stopwatch;
+                if was_running && stopwatch && !timer.elapsed.is_zero() {
+                    self.history.push(history::Record {
+                        name: timer.name.clone(),
+                        duration: timer.elapsed,
+                        finished_at: SystemTime::now(),
+                        mode: timer::Mode::Stopwatch,
+                    });
+                    if let Err(err) = history::save(&self.history) {
+                        log::error!("failed to save history: {err}");
+                    }
+                }
+                if was_running {
+                    self.save_timers();
+                    self.publish(id, mqtt::Event::Stopped);
+                }
+                task
+            }
+            Msg::Lap(id) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.lap();
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Stopwatch((id, stopwatch)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.mode = if stopwatch {
+                    timer::Mode::Stopwatch
+                } else {
+                    timer::Mode::Countdown
+                };
+                // What was counted one way means nothing the other
+                timer.reset();
+                self.save_timers();
                 Task::none()
             }
             Msg::Reset(id) => {
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
-                timer.state = timer::State::Stopped;
-                timer.time = Duration::from_secs(0);
-                timer.update_elapsed_hms();
+                let was_running = timer.state == timer::State::Running;
+                timer.reset();
+                self.save_timers();
+                if was_running {
+                    self.publish(id, mqtt::Event::Stopped);
+                }
                 Task::none()
             }
             Msg::PlayNotification(id) => {
+                self.check_sound_path();
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
                 timer.state = timer::State::NotificationSound;
-                Task::done(Msg::Stop(id))
+                if self.audio_unavailable || timer.silent {
+                    return Task::done(Msg::AlarmDone(id));
+                }
+
+                if self.config.ring_until_dismissed {
+                    // Stays in `NotificationSound` until stopped from the card
+                    let alarm = utils::AlarmHandle::default();
+                    self.alarms.insert(id, alarm.clone());
+                    return Task::batch([
+                        Task::perform(
+                            utils::play_notification_sound_in_background(
+                                self.config.sound_path.clone(),
+                                self.config.volume,
+                                utils::Repeat::UntilStopped(alarm),
+                            ),
+                            Msg::NotificationSoundFinished,
+                        ),
+                        self.request_attention(true),
+                    ]);
+                }
+
+                Task::batch([
+                    self.request_attention(true),
+                    Task::perform(
+                        utils::play_notification_sound_in_background(
+                            self.config.sound_path.clone(),
+                            self.config.volume,
+                            utils::Repeat::Times(
+                                timer.alarm_repeats.unwrap_or(self.config.alarm_repeats),
+                            ),
+                        ),
+                        Msg::NotificationSoundFinished,
+                    ),
+                    Task::done(Msg::AlarmDone(id)),
+                ])
             }
-            Msg::Tick(id) => {
+            Msg::AlarmDone(id) => {
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
-                println!(
+                // Nobody has to dismiss a scheduled timer, it goes off again next time
+                if !self.config.hold_finished || timer.schedule.is_some() {
+                    return Task::done(Msg::Stop(id));
+                }
+                timer.state = timer::State::Finished;
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Tick(id) => {
+                let position = self.timers.iter().position(|t| t.id == id).unwrap();
+                let now = Instant::now();
+                // Countdowns don't care, but schedules are meant for a time of day
+                if self.timers[position].clock_jumped(now, SystemTime::now()) {
+                    log::info!("the system clock changed, re-arming schedules");
+                    if timer::rearm_schedules(&mut self.timers, chrono::Local::now()) {
+                        self.save_timers();
+                    }
+                }
+                let timer = &mut self.timers[position];
+                log::debug!(
                     "TICK. CURRENT TIME: {:?}, STATE: {:?}",
-                    timer.time, timer.state
+                    timer.time,
+                    timer.state
                 );
 
-                if timer.state != timer::State::Running {
-                    return Task::none();
-                }
-
-                if timer.time <= Duration::from_secs(1) {
-                    if let Err(err) = notify_rust::Notification::new()
-                        .summary("Timer is done!")
-                        .body("Your timer has finished")
-                        .appname("oxyclock")
-                        .show()
-                    {
-                        eprintln!("failed to send notification: {err}");
+                let before = timer.time;
+                if !timer.tick(now) {
+                    log::debug!(
+                        "UPDATE TIME. TIME: {:?}, ELAPSED: {:?}",
+                        timer.time,
+                        timer.elapsed
+                    );
+                    if self.config.announce && !timer.silent {
+                        let milestones = timer
+                            .announce_at
+                            .as_deref()
+                            .unwrap_or(&self.config.announce_at);
+                        if let Some(milestone) = timer::crossed(before, timer.time, milestones) {
+                            utils::announce(milestone);
+                        }
                     }
-
-                    timer.time = Duration::from_secs(0);
-                    timer.update_elapsed_hms();
-
-                    return Task::done(Msg::PlayNotification(id));
+                    // Only ticks that don't finish the timer get here, so the
+                    // ticking never overlaps the alarm
+                    let ticking = timer.mode == timer::Mode::Countdown
+                        && timer.time.as_secs() < self.config.final_warning.seconds
+                        && !timer.silent
+                        && !self.audio_unavailable;
+                    return match self.config.tick_sound.clone() {
+                        Some(path) if ticking && path.exists() => Task::perform(
+                            utils::play_notification_sound_in_background(
+                                Some(path),
+                                self.config.volume,
+                                utils::Repeat::Times(1),
+                            ),
+                            Msg::NotificationSoundFinished,
+                        ),
+                        _ => Task::none(),
+                    };
                 }
 
-                let tick = Duration::from_secs(1);
-                timer.time -= tick;
-                timer.elapsed += tick;
-                println!(
-                    "UPDATE TIME. TIME: {:?}, ELAPSED: {:?}",
-                    timer.time, timer.elapsed
+                let name = timer.display_name(position);
+                let (webhook, break_started) = finish_timer(
+                    timer,
+                    &name,
+                    &mut self.history,
+                    self.mqtt.as_ref(),
+                    &self.config,
                 );
+
+                let alarm = if !break_started {
+                    Task::done(Msg::PlayNotification(id))
+                } else if self.audio_unavailable || timer.silent {
+                    Task::none()
+                } else {
+                    // Ring once without stopping, the timer keeps running for the break
+                    Task::perform(
+                        utils::play_notification_sound_in_background(
+                            self.config.sound_path.clone(),
+                            self.config.volume,
+                            utils::Repeat::Times(1),
+                        ),
+                        Msg::NotificationSoundFinished,
+                    )
+                };
+                // `--watch` would otherwise count on from the last save, stuck at zero
+                self.dirty = true;
+                Task::batch([webhook.map_or_else(Task::none, webhook::Call::task), alarm])
+            }
+            Msg::KeypadField((id, field)) => {
+                self.focused = Some((id, field));
+                Task::none()
+            }
+            Msg::Keypad((id, key)) => {
+                let field = self.keypad_field(id);
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                if self.config.digit_entry {
+                    let mut digits = format!("{}{}{}", timer.hours, timer.minutes, timer.seconds);
+                    match key {
+                        KeypadKey::Digit(digit) => digits.push_str(&digit.to_string()),
+                        KeypadKey::Backspace => {
+                            digits.pop();
+                        }
+                        KeypadKey::Clear => digits.clear(),
+                    }
+                    (timer.hours, timer.minutes, timer.seconds) = timer::shift_digits(&digits);
+                    self.focused = Some((id, Field::Hours));
+                    return Task::none();
+                }
+                let value = match field {
+                    Field::Hours => &mut timer.hours,
+                    Field::Minutes => &mut timer.minutes,
+                    _ => &mut timer.seconds,
+                };
+                *value = keypad_value(field, value, key);
+                self.focused = Some((id, field));
+                Task::none()
+            }
+            Msg::Dial((id, duration)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.set_hms(duration);
+                timer.error = None;
+                Task::none()
+            }
+            Msg::TouchMode(enabled) => {
+                self.config.touch_mode = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::Digits(Time { id, time }) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                (timer.hours, timer.minutes, timer.seconds) = timer::shift_digits(&time);
+                self.focused = Some((id, Field::Hours));
                 Task::none()
             }
             Msg::Hours(Time { id, time }) => {
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
                 timer.hours = time;
+                self.focused = Some((id, Field::Hours));
                 Task::none()
             }
             Msg::Minutes(Time { id, time }) => {
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
                 timer.minutes = time;
+                self.focused = Some((id, Field::Minutes));
                 Task::none()
             }
             Msg::Seconds(Time { id, time }) => {
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
                 timer.seconds = time;
+                self.focused = Some((id, Field::Seconds));
                 Task::none()
             }
             Msg::Name((id, name)) => {
                 let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
                 timer.name = name;
-                self.save_state(&self.timers);
+                self.focused = Some((id, Field::Name));
+                self.dirty = true;
                 Task::none()
             }
-        }
-    }
-
-    fn subscription(&self) -> Subscription<Msg> {
-        Subscription::batch(self.timers.iter().map(|t| t.subscription()))
-    }
-
-    fn theme(&self, _window_id: window::Id) -> theme::Theme {
-        custom_theme::arc_dark()
-    }
-
-    fn load_state() -> (Oxyclock, Task<Msg>) {
-        // Since I don't care about Windows
-        #[allow(deprecated)]
-        let mut path = std::env::home_dir().unwrap();
-        path.push(std::path::Path::new(".local/state/oxyclock/state.json"));
-        let state_file = File::open(path).unwrap();
-        let reader = BufReader::new(state_file);
-        let timers: Vec<timer::Timer> = serde_json::from_reader(reader).unwrap();
-        let state = Oxyclock { timers };
-        (state, Task::none())
-    }
+            Msg::AlarmRepeats((id, repeats)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.alarm_repeats = Some(repeats);
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Break((id, length)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.after_finish = length;
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Notify((id, notify)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.notify = notify;
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Silent((id, silent)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.silent = silent;
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Note((id, action)) => {
+                if let Some(content) = self.notes.get_mut(&id) {
+                    content.perform(action);
+                    let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                    timer.note = content.text().trim_end().to_string();
+                    self.dirty = true;
+                }
+                Task::none()
+            }
+            Msg::ToggleNote(id) => {
+                if self.notes.remove(&id).is_none() {
+                    let timer = self.timers.iter().find(|t| t.id == id).unwrap();
+                    self.notes
+                        .insert(id, text_editor::Content::with_text(&timer.note));
+                }
+                Task::none()
+            }
+            Msg::HoldFinished(enabled) => {
+                self.config.hold_finished = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::AfterFinish(after_finish) => {
+                self.config.after_finish = after_finish;
+                self.config.save();
+                Task::none()
+            }
+            Msg::RequestAttention(enabled) => {
+                self.config.request_attention = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::PauseWhenAway(enabled) => {
+                self.config.pause_when_away = enabled;
+                self.config.save();
+                if !enabled {
+                    self.away_paused.clear();
+                }
+                Task::none()
+            }
+            Msg::CheckAway => Task::perform(utils::session_away(), Msg::Away),
+            Msg::Away(Some(true)) if self.away_paused.is_empty() => {
+                self.away_paused = self
+                    .timers
+                    .iter()
+                    // Schedules go by the clock and stopwatches time the absence too
+                    .filter(|timer| {
+                        timer.state == timer::State::Running
+                            && timer.mode == timer::Mode::Countdown
+                            && !is_scheduled(timer)
+                    })
+                    .map(|timer| timer.id)
+                    .collect();
+                Task::batch(self.away_paused.iter().map(|id| Task::done(Msg::Stop(*id))))
+            }
+            Msg::Away(Some(false)) => {
+                let paused = std::mem::take(&mut self.away_paused);
+                // Anything started, reset or deleted in the meantime is left alone.
+                // Resuming isn't a fresh start, no start sound or remembered duration.
+                let mut resumed = Vec::new();
+                for timer in &mut self.timers {
+                    if !paused.contains(&timer.id) || !timer.is_paused() {
+                        continue;
+                    }
+                    match timer.start(self.config.max_duration) {
+                        Ok(_) => resumed.push(timer.id),
+                        Err(err) => log::warn!("failed to resume {}: {err}", timer.id),
+                    }
+                }
+                if !resumed.is_empty() {
+                    self.save_timers();
+                }
+                for id in resumed {
+                    self.publish(id, mqtt::Event::Started);
+                }
+                Task::none()
+            }
+            Msg::Away(_) => Task::none(),
+            Msg::RoundTo(step) => {
+                self.config.round_to = step;
+                self.config.save();
+                Task::none()
+            }
+            Msg::Announce(enabled) => {
+                self.config.announce = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::ScheduleDay((id, day)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                let was_scheduled = is_scheduled(timer);
+                let schedule = timer.schedule.get_or_insert_with(Default::default);
+                schedule.days[day] = !schedule.days[day];
+                self.apply_schedule(id, was_scheduled);
+                Task::none()
+            }
+            Msg::ScheduleTime((id, input)) => {
+                let Ok(at) = chrono::NaiveTime::parse_from_str(input.trim(), "%H:%M") else {
+                    self.schedule_inputs.insert(id, input);
+                    return Task::none();
+                };
+                self.schedule_inputs.remove(&id);
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                let was_scheduled = is_scheduled(timer);
+                timer.schedule.get_or_insert_with(Default::default).at =
+                    at.hour() * 60 + at.minute();
+                self.apply_schedule(id, was_scheduled);
+                Task::none()
+            }
+            Msg::AnnounceAt((id, input)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                if input.trim().is_empty() {
+                    timer.announce_at = None;
+                    self.announce_inputs.remove(&id);
+                    return Task::none();
+                }
+                let parsed: Option<Vec<Duration>> =
+                    input.split(',').map(utils::parse_duration).collect();
+                match parsed {
+                    Some(milestones) if !input.trim_end().ends_with(',') => {
+                        timer.announce_at = Some(milestones);
+                        self.announce_inputs.remove(&id);
+                    }
+                    _ => {
+                        self.announce_inputs.insert(id, input);
+                    }
+                }
+                Task::none()
+            }
+            Msg::ShowElapsed(show) => {
+                self.config.show_elapsed = show;
+                self.config.save();
+                Task::none()
+            }
+            Msg::DigitEntry(enabled) => {
+                self.config.digit_entry = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::ReduceMotion(enabled) => {
+                self.config.reduce_motion = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::Animate => {
+                // Wall-clock phase, so every pulsing card stays in step
+                let millis = SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis();
+                let phase = (millis % PULSE_PERIOD_MS) as f32 / PULSE_PERIOD_MS as f32;
+                self.pulse = (1.0 - (phase * std::f32::consts::TAU).cos()) / 2.0;
+                Task::none()
+            }
+            Msg::ShowEndsAt(show) => {
+                self.config.show_ends_at = show;
+                self.config.save();
+                Task::none()
+            }
+            Msg::Clock24h(enabled) => {
+                self.config.clock_24h = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::ShowCreated(show) => {
+                self.config.show_created = show;
+                self.config.save();
+                Task::none()
+            }
+            Msg::NumberDuplicates(number) => {
+                self.config.number_duplicates = number;
+                self.config.save();
+                Task::none()
+            }
+            Msg::ShowPercentage(show) => {
+                self.config.show_percentage = show;
+                self.config.save();
+                Task::none()
+            }
+            Msg::Percentage(shown) => {
+                self.config.percentage = shown;
+                self.config.save();
+                Task::none()
+            }
+            Msg::TogglePin(id) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.pinned = !timer.pinned;
+                self.save_timers();
+                Task::none()
+            }
+            Msg::Icon((id, icon)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.icon = (!icon.trim().is_empty()).then_some(icon);
+                self.dirty = true;
+                Task::none()
+            }
+            Msg::Webhook((id, url)) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                timer.webhook = (!url.is_empty()).then_some(url);
+                self.dirty = true;
+                Task::none()
+            }
+            Msg::WebhookSent(result) => match result {
+                Ok(()) => Task::none(),
+                Err(err) => {
+                    log::warn!("webhook failed: {err}");
+                    Task::done(Msg::ShowToast(t_fmt("webhook_failed", &[("error", &err)])))
+                }
+            },
+            Msg::CopyTimer(id) => {
+                let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+                match timer.to_share_string() {
+                    Ok(share) => clipboard::write(share)
+                        .chain(Task::done(Msg::ShowToast(t("timer_copied").to_string()))),
+                    Err(err) => {
+                        timer.error =
+                            Some(t_fmt("invalid_duration", &[("error", &err.to_string())]));
+                        Task::none()
+                    }
+                }
+            }
+            Msg::PasteTimer => clipboard::read().map(Msg::TimerPasted),
+            Msg::TimerPasted(contents) => {
+                if let Some(timer) = contents
+                    .as_deref()
+                    .and_then(timer::Timer::from_share_string)
+                {
+                    self.timers.push(timer);
+                    self.save_timers();
+                    Task::done(Msg::ShowToast(t("timer_pasted").to_string()))
+                } else {
+                    Task::done(Msg::ShowToast(t("nothing_to_paste").to_string()))
+                }
+            }
+            Msg::ThemeSelected(theme) => {
+                self.config.theme = theme;
+                self.config.save();
+                Task::none()
+            }
+            Msg::ViewPresetSelected(preset) => {
+                self.config.apply_view_preset(&preset);
+                self.config.save();
+                self.accent_input = preset.accent.unwrap_or_default();
+                Task::none()
+            }
+            Msg::ViewPresetNameInput(name) => {
+                self.new_view_preset = name;
+                Task::none()
+            }
+            Msg::SaveViewPreset => {
+                let name = self.new_view_preset.trim().to_string();
+                if name.is_empty() {
+                    return Task::none();
+                }
+                // Saving under an existing name overwrites that preset
+                let preset = self.config.view_preset(name);
+                match self
+                    .config
+                    .view_presets
+                    .iter_mut()
+                    .find(|saved| saved.name == preset.name)
+                {
+                    Some(saved) => *saved = preset,
+                    None => self.config.view_presets.push(preset),
+                }
+                self.config.save();
+                self.new_view_preset.clear();
+                Task::done(Msg::ShowToast(t_fmt(
+                    "view_preset_saved",
+                    &[("name", &name)],
+                )))
+            }
+            Msg::NotificationUrgency(urgency) => {
+                self.config.notification_urgency = urgency;
+                self.config.save();
+                Task::none()
+            }
+            Msg::NotificationTimeout(timeout) => {
+                self.config.notification_timeout = timeout;
+                self.config.save();
+                Task::none()
+            }
+            Msg::AccentInput(input) => {
+                if input.is_empty() {
+                    self.config.accent = None;
+                    self.config_dirty = true;
+                } else if let Some(color) = custom_theme::parse_hex(&input) {
+                    self.config.accent = Some(custom_theme::to_hex(color));
+                    self.config_dirty = true;
+                }
+                self.accent_input = input;
+                Task::none()
+            }
+            Msg::SoundPathChanged(path) => {
+                self.config.sound_path = (!path.is_empty()).then(|| path.into());
+                self.config_dirty = true;
+                self.sound_warning = None;
+                self.warned_sound = None;
+                Task::none()
+            }
+            Msg::CheckSoundPath => {
+                self.check_sound_path();
+                Task::none()
+            }
+            Msg::DismissSoundWarning => {
+                self.sound_warning = None;
+                Task::none()
+            }
+            Msg::TickSoundPathChanged(path) => {
+                self.config.tick_sound = (!path.is_empty()).then(|| path.into());
+                self.config_dirty = true;
+                Task::none()
+            }
+            Msg::StartSoundPathChanged(path) => {
+                self.config.start_sound = (!path.is_empty()).then(|| path.into());
+                self.config_dirty = true;
+                Task::none()
+            }
+            Msg::VolumeChanged(volume) => {
+                self.config.volume = volume;
+                self.config.save();
+                Task::none()
+            }
+            Msg::FinalWarning(enabled) => {
+                self.config.final_warning.enabled = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::FinalWarningSeconds(seconds) => {
+                self.config.final_warning.seconds = seconds;
+                self.config.save();
+                Task::none()
+            }
+            Msg::FinalWarningPulse(pulse) => {
+                self.config.final_warning.pulse = pulse;
+                self.config.save();
+                Task::none()
+            }
+            Msg::DefaultAlarmRepeats(repeats) => {
+                self.config.alarm_repeats = repeats;
+                self.config.save();
+                Task::none()
+            }
+            Msg::RingUntilDismissed(enabled) => {
+                self.config.ring_until_dismissed = enabled;
+                self.config.save();
+                Task::none()
+            }
+            Msg::DailyGoalChanged(goal) => {
+                self.config.daily_goal = goal;
+                self.config.save();
+                Task::none()
+            }
+            Msg::SortSelected(sort) => {
+                self.config.sort = sort;
+                self.config.save();
+                Task::none()
+            }
+            Msg::ScaleFactorChanged(factor) => {
+                self.config.scale_factor = factor;
+                self.config.save();
+                Task::none()
+            }
+            Msg::TestSound => {
+                self.sound_error = None;
+                Task::perform(
+                    utils::play_notification_sound_in_background(
+                        self.config.sound_path.clone(),
+                        self.config.volume,
+                        utils::Repeat::Times(1),
+                    ),
+                    Msg::TestSoundFinished,
+                )
+            }
+            Msg::TestSoundFinished(result) => {
+                self.sound_error = result.as_ref().err().map(|err| err.to_string());
+                self.handle_sound_result(result);
+                Task::none()
+            }
+            Msg::NotificationSoundFinished(result) => {
+                if let Err(err) = &result {
+                    log::warn!("failed to play notification sound: {err}");
+                }
+                self.handle_sound_result(result);
+                Task::none()
+            }
+            Msg::RetrySave => {
+                self.save_timers();
+                Task::none()
+            }
+            Msg::DismissSaveError => {
+                self.save_error = None;
+                Task::none()
+            }
+            Msg::ShowToast(message) => {
+                // The same message twice in a row would only look stuck
+                if self.toasts.back() != Some(&message) {
+                    self.toasts.push_back(message);
+                }
+                if self.toast_expires.is_none() {
+                    self.toast_expires = Some(Instant::now() + TOAST_DURATION);
+                }
+                Task::none()
+            }
+            Msg::ToastTick(now) => {
+                if self.toast_expires.is_some_and(|expires| expires <= now) {
+                    self.toasts.pop_front();
+                    self.toast_expires = (!self.toasts.is_empty()).then(|| now + TOAST_DURATION);
+                }
+                Task::none()
+            }
+            Msg::DismissAudioWarning => {
+                self.audio_warning = false;
+                Task::none()
+            }
+            Msg::ImportPathChanged(path) => {
+                self.import_path = path;
+                Task::none()
+            }
+            Msg::ImportCsv(path) => {
+                match std::fs::read_to_string(&path) {
+                    Ok(content) => {
+                        let import = csv::import_timers(&content);
+                        let count = import.timers.len().to_string();
+                        let message = t_fmt("timers_imported", &[("count", &count)]);
+                        let result = if import.skipped.is_empty() {
+                            message.clone()
+                        } else {
+                            let lines: Vec<String> =
+                                import.skipped.iter().map(|line| line.to_string()).collect();
+                            t_fmt(
+                                "timers_imported_skipped",
+                                &[("count", &count), ("lines", &lines.join(", "))],
+                            )
+                        };
+                        self.timers.extend(import.timers);
+                        self.save_timers();
+                        self.import_result = Some(result);
+                        return Task::done(Msg::ShowToast(message));
+                    }
+                    Err(err) => {
+                        log::warn!("failed to read {}: {err}", path.display());
+                        self.import_result =
+                            Some(t_fmt("read_failed", &[("error", &err.to_string())]));
+                    }
+                }
+                Task::none()
+            }
+            Msg::ExportPathChanged(path) => {
+                self.export_path = path;
+                Task::none()
+            }
+            Msg::ExportCsv(path) => {
+                self.export_result = match std::fs::write(&path, csv::export_history(&self.history))
+                {
+                    Ok(()) => Some(t_fmt(
+                        "sessions_exported",
+                        &[("count", &self.history.len().to_string())],
+                    )),
+                    Err(err) => {
+                        log::warn!("failed to write {}: {err}", path.display());
+                        Some(t_fmt("write_failed", &[("error", &err.to_string())]))
+                    }
+                };
+                Task::none()
+            }
+            Msg::FlushState => {
+                if self.dirty {
+                    self.save_timers();
+                }
+                if self.config_dirty {
+                    self.config.save();
+                    self.config_dirty = false;
+                }
+                if self.templates_dirty {
+                    self.save_templates();
+                    self.templates_dirty = false;
+                }
+                Task::none()
+            }
+            Msg::CloseRequested => {
+                // Running timers are written with the time they have left right now, the
+                // next launch offers to resume them
+                let finished = settle_on_close(&mut self.timers, Instant::now(), &self.config);
+                if !finished.is_empty() {
+                    self.history.extend(finished);
+                    if let Err(err) = history::save(&self.history) {
+                        log::error!("failed to save history: {err}");
+                    }
+                }
+                for timer in &mut self.timers {
+                    // Still undecided, so they're asked about again next time
+                    if self.interrupted.contains(&timer.id) {
+                        timer.state = timer::State::Running;
+                    }
+                }
+                for (_, alarm) in self.alarms.drain() {
+                    alarm.stop();
+                }
+                self.save_timers();
+                if self.config_dirty {
+                    self.config.save();
+                }
+                if self.templates_dirty {
+                    self.save_templates();
+                }
+                iced::exit()
+            }
+            Msg::ResumeSession => {
+                let now = SystemTime::now();
+                for id in std::mem::take(&mut self.interrupted) {
+                    if let Some(timer) = self.timers.iter_mut().find(|t| t.id == id) {
+                        timer.catch_up(now);
+                    }
+                }
+                self.save_timers();
+                Task::none()
+            }
+            Msg::DiscardSession => {
+                for id in std::mem::take(&mut self.interrupted) {
+                    if let Some(timer) = self.timers.iter_mut().find(|t| t.id == id) {
+                        timer.reset();
+                    }
+                }
+                self.save_timers();
+                Task::none()
+            }
+            Msg::TimersScrolled(viewport) => {
+                self.timers_scroll = viewport.absolute_offset();
+                Task::none()
+            }
+            Msg::Navigate(page) => {
+                // Stats are only recomputed when entering the dashboard, not every tick
+                if page == Page::Dashboard {
+                    self.summary = Some(stats::summarize(&self.history, chrono::Local::now()));
+                }
+                self.page = page;
+                if page == Page::Timers {
+                    scrollable::scroll_to(timers_scroll_id(), self.timers_scroll)
+                } else {
+                    Task::none()
+                }
+            }
+            Msg::DetectSystemTheme => {
+                self.system_dark = detect_system_dark();
+                Task::none()
+            }
+            Msg::FocusNext => self.move_focus(Field::next),
+            Msg::FocusPrevious => self.move_focus(Field::previous),
+        }
+    }
+
+    fn subscription(&self) -> Subscription<Msg> {
+        let interval = self.config.tick_interval();
+        let timers =
+            Subscription::batch(self.timers.iter().map(|t| timer_subscription(t, interval)));
+        let keys = keyboard::on_key_press(|key, modifiers| match key.as_ref() {
+            keyboard::Key::Named(keyboard::key::Named::Tab) if modifiers.shift() => {
+                Some(Msg::FocusPrevious)
+            }
+            keyboard::Key::Named(keyboard::key::Named::Tab) => Some(Msg::FocusNext),
+            keyboard::Key::Named(keyboard::key::Named::Escape) => Some(Msg::ClosePalette),
+            // Matched against the configured shortcuts in `update`, this can't see the config.
+            // Focused text inputs capture the key press, so typing never gets here.
+            _ => Some(Msg::KeyPressed(key, modifiers)),
+        });
+
+        // dark-light has no change notifications, so poll while following the system
+        let system_theme = if self.config.theme == config::ThemeChoice::System {
+            iced::time::every(Duration::from_secs(5)).map(|_| Msg::DetectSystemTheme)
+        } else {
+            Subscription::none()
+        };
+
+        // Typing only marks the state dirty, it hits the disk at most every 500ms
+        let flush = if self.dirty || self.config_dirty || self.templates_dirty {
+            iced::time::every(Duration::from_millis(500)).map(|_| Msg::FlushState)
+        } else {
+            Subscription::none()
+        };
+        // logind has no polling-free API without a D-Bus dependency
+        let away = if self.config.pause_when_away {
+            iced::time::every(Duration::from_secs(5)).map(|_| Msg::CheckAway)
+        } else {
+            Subscription::none()
+        };
+        let animation = if !self.config.reduce_motion && self.timers.iter().any(is_alarmed) {
+            window::frames().map(|_| Msg::Animate)
+        } else {
+            Subscription::none()
+        };
+        let toasts = if self.toasts.is_empty() {
+            Subscription::none()
+        } else {
+            iced::time::every(Duration::from_millis(250)).map(Msg::ToastTick)
+        };
+        let closed = window::close_requests().map(|_| Msg::CloseRequested);
+        let resized = window::resize_events().map(|(_, size)| Msg::WindowResized(size));
+
+        let instances = Subscription::run(instance::focus_requests);
+
+        Subscription::batch([
+            timers,
+            keys,
+            system_theme,
+            flush,
+            away,
+            animation,
+            toasts,
+            closed,
+            resized,
+            instances,
+        ])
+    }
+
+    fn window_settings(&self) -> window::Settings {
+        if self.config.mini_mode {
+            window::Settings {
+                size: Size::new(240.0, 160.0),
+                level: window::Level::AlwaysOnTop,
+                exit_on_close_request: false,
+                ..window::Settings::default()
+            }
+        } else {
+            window::Settings {
+                size: self.full_size,
+                // One full timer card plus the scrollbar and the top bar
+                min_size: Some(Size::new(440.0, 420.0)),
+                // Closing goes through `Msg::CloseRequested` to save before exiting
+                exit_on_close_request: false,
+                ..window::Settings::default()
+            }
+        }
+    }
+
+    fn handle_sound_result(&mut self, result: Result<(), utils::SoundError>) {
+        match result {
+            Err(utils::SoundError::NoDevice) => {
+                // Only warn the first time audio turns out to be missing
+                self.audio_warning |= !self.audio_unavailable;
+                self.audio_unavailable = true;
+            }
+            _ => self.audio_unavailable = false,
+        }
+    }
+
+    fn move_focus(&mut self, step: fn(Field) -> Field) -> Task<Msg> {
+        // Keep focus cycling inside the fields of a single stopped timer
+        let target = match self.focused {
+            Some((id, field)) if self.is_editable(id) => Some((id, step(field))),
+            _ => self
+                .timers
+                .iter()
+                .find(|t| t.state != timer::State::Running)
+                .map(|t| (t.id, Field::Hours)),
+        };
+
+        match target {
+            Some((id, field)) => {
+                self.focused = Some((id, field));
+                text_input::focus(input_id(id, field))
+            }
+            None => Task::none(),
+        }
+    }
+
+    /// Drives the warning color, which blinks with every other second when pulsing
+    fn in_final_seconds(&self, timer: &timer::Timer) -> bool {
+        let secs = timer.time.as_secs();
+        let warning = &self.config.final_warning;
+        timer.mode == timer::Mode::Countdown
+            && warning.enabled
+            && secs < warning.seconds
+            && !(warning.pulse && secs % 2 == 1)
+    }
+
+    fn dial(&self, timer: &timer::Timer, started: bool) -> Element<'_, Msg> {
+        let turn = self.config.dial_turn.max(Duration::from_secs(60));
+        let fraction = if started {
+            timer.remaining_percentage() as f32 / 100.0
+        } else {
+            let duration = timer
+                .get_duration(self.config.max_duration)
+                .unwrap_or_default();
+            duration.as_secs_f32() / turn.as_secs_f32()
+        };
+        let ringing = self.alarms.contains_key(&timer.id);
+        canvas(dial::Dial {
+            timer_id: timer.id,
+            fraction,
+            turn: (!started && !ringing).then_some(turn),
+        })
+        .width(80)
+        .height(80)
+        .into()
+    }
+
+    /// Removes the timers, keeping them around for an undo
+    fn delete_timers(&mut self, ids: &[Uuid]) {
+        let mut deleted = Vec::new();
+        // Back to front, so the positions of the timers still to remove don't shift
+        for index in (0..self.timers.len()).rev() {
+            let id = self.timers[index].id;
+            if !ids.contains(&id) {
+                continue;
+            }
+            if let Some(alarm) = self.alarms.remove(&id) {
+                alarm.stop();
+            }
+            let mut timer = self.timers.remove(index);
+            // Comes back stopped, an undo shouldn't ring a timer that ran out meanwhile
+            if timer.state != timer::State::Stopped {
+                timer.state = timer::State::Stopped;
+                timer.update_elapsed_hms();
+            }
+            self.selected.remove(&id);
+            if self.focused.is_some_and(|(focused, _)| focused == id) {
+                self.focused = None;
+            }
+            deleted.push((index, timer));
+        }
+        deleted.reverse();
+        self.deleted = deleted;
+        self.save_timers();
+    }
+
+    /// Warns once about a configured alarm sound that can't be played, the
+    /// bundled one rings in its place
+    fn check_sound_path(&mut self) {
+        let Some(path) = self.config.sound_path.clone() else {
+            return;
+        };
+        match utils::check_sound(&path) {
+            Ok(()) => {
+                self.sound_warning = None;
+                self.warned_sound = None;
+            }
+            Err(err) if self.warned_sound.as_ref() != Some(&path) => {
+                self.sound_warning = Some(t_fmt(
+                    "sound_unplayable",
+                    &[
+                        ("path", &path.display().to_string()),
+                        ("error", &err.to_string()),
+                    ],
+                ));
+                self.warned_sound = Some(path);
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Flashes the taskbar entry until the window gets focus, or stops flashing
+    fn request_attention(&self, urgent: bool) -> Task<Msg> {
+        match self.window {
+            Some(window) if self.config.request_attention => window::request_user_attention(
+                window,
+                urgent.then_some(window::UserAttention::Critical),
+            ),
+            _ => Task::none(),
+        }
+    }
+
+    fn publish(&self, id: Uuid, event: mqtt::Event) {
+        let Some(publisher) = &self.mqtt else {
+            return;
+        };
+        if let Some(position) = self.timers.iter().position(|t| t.id == id) {
+            publisher.publish(id, &self.timers[position].display_name(position), event);
+        }
+    }
+
+    /// Palette entries matching `query`, ignoring case
+    fn commands(&self, query: &str) -> Vec<(String, Command)> {
+        let query = query.to_lowercase();
+        [
+            (t("add_timer").to_string(), Command::AddTimer),
+            (t("start_all").to_string(), Command::StartAll),
+            (t("switch_theme").to_string(), Command::NextTheme),
+            (
+                t("open_settings").to_string(),
+                Command::Open(Page::Settings),
+            ),
+            (t("open_stats").to_string(), Command::Open(Page::Dashboard)),
+            (
+                t("open_templates").to_string(),
+                Command::Open(Page::Templates),
+            ),
+        ]
+        .into_iter()
+        .chain(self.timers.iter().enumerate().map(|(position, timer)| {
+            (
+                t_fmt("go_to_timer", &[("name", &timer.display_name(position))]),
+                Command::JumpTo(timer.id),
+            )
+        }))
+        .filter(|(label, _)| label.to_lowercase().contains(&query))
+        .collect()
+    }
+
+    /// Strip of the running timers above the list, none when nothing runs
+    fn running_bar(&self) -> Option<Scrollable<'_, Msg>> {
+        let running: Vec<(Uuid, String, String)> = self
+            .sorted_timers()
+            .into_iter()
+            .filter(|(_, timer)| timer.state == timer::State::Running)
+            .map(|(position, timer)| {
+                (
+                    timer.id,
+                    timer.display_name(position),
+                    utils::format_hms(timer.time),
+                )
+            })
+            .collect();
+        (!running.is_empty()).then(|| running_bar(running))
+    }
+
+    /// Scrolls the list to the card of a timer, by its position in display order
+    fn scroll_to(&self, id: Uuid) -> Task<Msg> {
+        let sorted = self.sorted_timers();
+        let position = sorted.iter().position(|(_, t)| t.id == id).unwrap_or(0);
+        let y = position as f32 / (sorted.len().max(1) - 1).max(1) as f32;
+        scrollable::snap_to(timers_scroll_id(), scrollable::RelativeOffset { x: 0.0, y })
+    }
+
+    /// Re-arms a timer after its schedule changed. One that no longer has a day to
+    /// go off on stops counting down to the old occurrence.
+    fn apply_schedule(&mut self, id: Uuid, was_scheduled: bool) {
+        let timer = self.timers.iter_mut().find(|t| t.id == id).unwrap();
+        if !timer.arm_schedule(chrono::Local::now()) && was_scheduled {
+            timer.reset();
+        }
+        self.save_timers();
+    }
+
+    /// Whether another timer goes by the same name
+    fn has_duplicate_name(&self, timer: &timer::Timer) -> bool {
+        self.timers
+            .iter()
+            .any(|other| other.id != timer.id && timer::same_name(&timer.name, &other.name))
+    }
+
+    /// Gives the timer a numbered name if that's configured and its name is taken.
+    /// Not done while typing, "Tea" would turn into "Tea (2)" before "Teapot".
+    fn number_duplicate(&mut self, id: Uuid) {
+        if !self.config.number_duplicates {
+            return;
+        }
+        let others: Vec<&str> = self
+            .timers
+            .iter()
+            .filter(|timer| timer.id != id)
+            .map(|timer| timer.name.as_str())
+            .collect();
+        let Some(timer) = self.timers.iter().find(|timer| timer.id == id) else {
+            return;
+        };
+        let name = timer::unique_name(&timer.name, &others);
+        if name != timer.name {
+            self.timers
+                .iter_mut()
+                .find(|timer| timer.id == id)
+                .unwrap()
+                .name = name;
+            self.dirty = true;
+        }
+    }
+
+    /// Display order only, `self.timers` is left as is
+    fn sorted_timers(&self) -> Vec<(usize, &timer::Timer)> {
+        let mut timers: Vec<(usize, &timer::Timer)> = self.timers.iter().enumerate().collect();
+        match self.config.sort {
+            config::SortOrder::Created => timers.sort_by_key(|(_, timer)| timer.created_at),
+            config::SortOrder::Name => timers.sort_by_key(|(_, timer)| timer.name.to_lowercase()),
+            config::SortOrder::Remaining => timers.sort_by_key(|(_, timer)| {
+                if timer.state == timer::State::Stopped {
+                    timer
+                        .get_duration(timer::MAX_DURATION)
+                        .unwrap_or(Duration::MAX)
+                } else {
+                    timer.time
+                }
+            }),
+        }
+        // Stable, so each group keeps the order picked above
+        timers.sort_by_key(|(_, timer)| !timer.pinned);
+        timers
+    }
+
+    /// Time field the keypad of a timer types into, hours unless another one was picked
+    fn keypad_field(&self, id: Uuid) -> Field {
+        match self.focused {
+            Some((focused, field)) if focused == id && field != Field::Name => field,
+            _ => Field::Hours,
+        }
+    }
+
+    fn is_editable(&self, id: Uuid) -> bool {
+        self.timers
+            .iter()
+            .any(|t| t.id == id && t.state != timer::State::Running)
+    }
+
+    fn theme(&self, _window_id: window::Id) -> theme::Theme {
+        let accent = self
+            .config
+            .accent
+            .as_deref()
+            .and_then(custom_theme::parse_hex);
+
+        match self.config.theme {
+            config::ThemeChoice::Dark => custom_theme::arc_dark(accent),
+            config::ThemeChoice::Light => custom_theme::arc_light(accent),
+            config::ThemeChoice::System if self.system_dark => custom_theme::arc_dark(accent),
+            config::ThemeChoice::System => custom_theme::arc_light(accent),
+            // Accent colors are ignored here, the palette is fixed for legibility
+            config::ThemeChoice::HighContrast => custom_theme::high_contrast(),
+        }
+    }
+
+    fn scale_factor(&self, _window_id: window::Id) -> f64 {
+        self.config.scale_factor()
+    }
+
+    fn load_state() -> (Oxyclock, Task<Msg>) {
+        let state = Oxyclock::default();
+        let mut state = Oxyclock {
+            timers: load_timers(&state.config.profile),
+            ..state
+        };
+        state.seed_timer();
+        state.interrupted = hold_interrupted(&mut state.timers, chrono::Local::now());
+        state.check_sound_path();
+        (state, Task::none())
+    }
+
+    /// Gives an empty list one timer to start from
+    fn seed_timer(&mut self) {
+        if self.timers.is_empty() {
+            self.timers.push(first_timer(&self.config));
+        }
+    }
+
+    fn save_state(&self, timers: &[timer::Timer]) -> io::Result<()> {
+        write_timers(&self.config.profile, timers)
+    }
+
+    fn save_timers(&mut self) {
+        self.dirty = false;
+        let result = self.save_state(&self.timers);
+        self.record_save_result(result);
+    }
+
+    fn save_templates(&self) {
+        if let Err(err) = templates::save(&self.templates) {
+            log::error!("failed to save templates: {err}");
+        }
+    }
+
+    /// Failed saves are reported in a banner, the timers stay in memory for a retry
+    fn record_save_result(&mut self, result: io::Result<()>) {
+        match result {
+            Ok(()) => self.save_error = None,
+            Err(err) => {
+                log::error!("failed to save state: {err}");
+                self.save_error = Some(t_fmt("save_failed", &[("error", &err.to_string())]));
+            }
+        }
+    }
+}
+
+fn load_timers(profile: &str) -> Vec<timer::Timer> {
+    let path = utils::state_path(profile);
+    let _lock = utils::lock_state(false)
+        .inspect_err(|err| log::warn!("failed to lock {}: {err}", path.display()));
+    match File::open(&path) {
+        Ok(state_file) => {
+            let reader = BufReader::new(state_file);
+            serde_json::from_reader(reader).unwrap_or_else(|err| {
+                log::error!("failed to parse {}: {err}", path.display());
+                Vec::new()
+            })
+        }
+        Err(err) => {
+            if err.kind() != std::io::ErrorKind::NotFound {
+                log::error!("failed to open {}: {err}", path.display());
+            }
+            Vec::new()
+        }
+    }
+}
+
+/// Writes `timer` over the saved timer with its id, or appends it. The file may be
+/// ordered differently from the list or miss the timer entirely.
+fn merge_timer(saved: &mut Vec<timer::Timer>, timer: &timer::Timer) {
+    match saved.iter_mut().find(|saved| saved.id == timer.id) {
+        Some(saved) => *saved = timer.clone(),
+        None => saved.push(timer.clone()),
+    }
+}
+
+/// Stops the timers that were running when the app closed and returns their ids.
+/// They don't count on until it's decided what happens to the time they missed,
+/// scheduled ones simply wait for their next occurrence.
+fn hold_interrupted(
+    timers: &mut [timer::Timer],
+    now: chrono::DateTime<chrono::Local>,
+) -> Vec<Uuid> {
+    let mut interrupted = Vec::new();
+    for timer in timers {
+        if timer.arm_schedule(now) {
+            continue;
+        }
+        if timer.state == timer::State::Running {
+            timer.state = timer::State::Stopped;
+            interrupted.push(timer.id);
+        }
+    }
+    interrupted
+}
+
+/// The timer an empty list starts with. `default_duration` fills it in, or else
+/// the last used duration when that's remembered.
+fn first_timer(config: &config::Config) -> timer::Timer {
+    let mut timer = timer::Timer::default();
+    if let Some(duration) = config.default_duration {
+        timer.set_hms(duration);
+    } else if config.remember_last_duration {
+        timer.set_hms(config.last_duration);
+    }
+    timer
+}
+
+/// Gets the timers ready to be saved as the app closes. A countdown running out
+/// right now finishes like on any tick, and its run is returned for the history.
+/// The alarm of a ringing one stops with the app, so it's dismissed like stopping
+/// it would, unless done timers are held until dismissed.
+fn settle_on_close(
+    timers: &mut Vec<timer::Timer>,
+    now: Instant,
+    config: &config::Config,
+) -> Vec<history::Record> {
+    let mut finished = Vec::new();
+    let mut removed = Vec::new();
+    for timer in timers.iter_mut() {
+        // Breaks aren't focus time
+        if timer.tick(now) && !timer.on_break {
+            finished.push(history::Record {
+                name: timer.name.clone(),
+                duration: timer.elapsed,
+                finished_at: SystemTime::now(),
+                mode: timer.mode,
+            });
+        }
+        if timer.state != timer::State::NotificationSound {
+            continue;
+        }
+        if config.hold_finished && timer.schedule.is_none() {
+            timer.state = timer::State::Finished;
+            continue;
+        }
+        timer.pause();
+        if !timer.arm_schedule(chrono::Local::now()) && config.after_finish.apply(timer) {
+            removed.push(timer.id);
+        }
+    }
+    timers.retain(|timer| !removed.contains(&timer.id));
+    finished
+}
+
+/// What a finish does besides the alarm, in the window and the daemon alike. The
+/// run goes into the history and out to the MQTT broker unless it was a break, the
+/// break starts if the timer has one and the notification shows. Returns the
+/// webhook call to make, each side has its own way, and whether the break started.
+fn finish_timer(
+    timer: &mut timer::Timer,
+    name: &str,
+    history: &mut Vec<history::Record>,
+    publisher: Option<&mqtt::Publisher>,
+    config: &config::Config,
+) -> (Option<webhook::Call>, bool) {
+    // Breaks aren't focus time
+    let break_over = timer.on_break;
+    let mut webhook = None;
+    if !break_over {
+        let finished_at = SystemTime::now();
+        history.push(history::Record {
+            name: timer.name.clone(),
+            duration: timer.elapsed,
+            finished_at,
+            mode: timer.mode,
+        });
+        webhook = webhook::Call::new(timer, name, finished_at);
+        if let Err(err) = history::save(history) {
+            log::error!("failed to save history: {err}");
+        }
+        if let Some(publisher) = publisher {
+            publisher.publish(timer.id, name, mqtt::Event::Finished);
+        }
+    }
+
+    let break_started = timer.start_break();
+    utils::notify_finished(
+        timer,
+        name,
+        break_over,
+        break_started,
+        config.notification_urgency,
+        config.notification_timeout,
+    );
+    (webhook, break_started)
+}
+
+/// Has a day picked on its schedule
+fn is_scheduled(timer: &timer::Timer) -> bool {
+    timer
+        .schedule
+        .as_ref()
+        .is_some_and(|schedule| schedule.days.contains(&true))
+}
+
+/// Went off and waits for the alarm to be stopped or the card dismissed
+fn is_alarmed(timer: &timer::Timer) -> bool {
+    matches!(
+        timer.state,
+        timer::State::NotificationSound | timer::State::Finished
+    )
+}
+
+/// Wakes every `interval`, or sooner when the timer is about to finish so a
+/// coarse interval doesn't delay the alarm
+fn timer_subscription(timer: &timer::Timer, interval: Duration) -> Subscription<Msg> {
+    log::debug!("SUBSCRIPTION. STATE: {:?}", timer.state);
+    // A stopwatch's time counts up, there's no end to wake up for
+    let interval = match timer.mode {
+        timer::Mode::Countdown => interval.min(timer.time),
+        timer::Mode::Stopwatch => interval,
+    }
+    .max(MIN_TICK);
+    match timer.state {
+        timer::State::Running => iced::time::every(interval)
+            .with(timer.id)
+            .map(|s| Msg::Tick(s.0)),
+        timer::State::NotificationSound | timer::State::Finished | timer::State::Stopped => {
+            Subscription::none()
+        }
+    }
+}
+
+fn write_timers(profile: &str, timers: &[timer::Timer]) -> io::Result<()> {
+    write_timers_to(&utils::state_path(profile), timers)
+}
+
+fn write_timers_to(path: &Path, timers: &[timer::Timer]) -> io::Result<()> {
+    let _lock = utils::lock_state(true)?;
+    std::fs::create_dir_all(path.parent().unwrap())?;
+    let file = File::create(path)?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, timers)?;
+    writer.flush()
+}
+
+fn detect_system_dark() -> bool {
+    dark_light::detect() != dark_light::Mode::Light
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Once;
+
+    /// Scratch directory standing in for the state directory of every test, each
+    /// test keeps to a profile of its own
+    fn scratch_dir() -> PathBuf {
+        static INIT: Once = Once::new();
+        let dir = std::env::temp_dir().join(format!("oxyclock-test-{}", std::process::id()));
+        INIT.call_once(|| utils::init_state_dir(Some(dir.clone())));
+        dir
+    }
+
+    #[test]
+    fn saving_where_the_directory_cant_be_made_fails() {
+        // Unlike a read-only directory this stops root as well
+        let dir = scratch_dir();
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("not-a-directory");
+        std::fs::write(&file, b"").unwrap();
+
+        let result = write_timers_to(&file.join("state.json"), &[timer::Timer::default()]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn concurrent_saves_leave_valid_json() {
+        let path = scratch_dir().join("concurrent-saves.json");
+        let writers: Vec<_> = [3, 40]
+            .into_iter()
+            .map(|count| {
+                let path = path.clone();
+                std::thread::spawn(move || {
+                    let timers: Vec<_> = (0..count).map(|_| timer::Timer::default()).collect();
+                    for _ in 0..25 {
+                        write_timers_to(&path, &timers).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let timers: Vec<timer::Timer> = serde_json::from_str(&content).unwrap();
+        assert!(timers.len() == 3 || timers.len() == 40);
+    }
+
+    fn named(name: &str) -> timer::Timer {
+        timer::Timer {
+            name: name.to_string(),
+            ..timer::Timer::default()
+        }
+    }
+
+    #[test]
+    fn merge_timer_matches_by_id_whatever_the_order() {
+        let (tea, eggs, rice) = (named("Tea"), named("Eggs"), named("Rice"));
+        // Memory has Tea, Eggs, Rice while the file has them the other way around
+        let edited = timer::Timer {
+            name: "Green tea".to_string(),
+            ..tea.clone()
+        };
+        let mut saved = vec![rice, eggs, tea];
+
+        merge_timer(&mut saved, &edited);
+        let names: Vec<&str> = saved.iter().map(|timer| timer.name.as_str()).collect();
+        assert_eq!(names, ["Rice", "Eggs", "Green tea"]);
+    }
+
+    #[test]
+    fn merge_timer_appends_a_timer_missing_from_the_file() {
+        let mut saved = vec![named("Tea")];
+        merge_timer(&mut saved, &named("Eggs"));
+        let names: Vec<&str> = saved.iter().map(|timer| timer.name.as_str()).collect();
+        assert_eq!(names, ["Tea", "Eggs"]);
+    }
+
+    fn seeded_duration(config: &config::Config) -> Duration {
+        first_timer(config)
+            .get_duration(timer::MAX_DURATION)
+            .unwrap()
+    }
+
+    #[test]
+    fn the_first_timer_prefers_the_default_duration() {
+        let config = config::Config {
+            default_duration: Some(Duration::from_secs(1500)),
+            remember_last_duration: true,
+            last_duration: Duration::from_secs(300),
+            ..config::Config::default()
+        };
+        assert_eq!(seeded_duration(&config), Duration::from_secs(1500));
+    }
+
+    #[test]
+    fn the_first_timer_falls_back_to_the_last_duration() {
+        let config = config::Config {
+            default_duration: None,
+            remember_last_duration: true,
+            last_duration: Duration::from_secs(300),
+            ..config::Config::default()
+        };
+        assert_eq!(seeded_duration(&config), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn the_first_timer_starts_empty_without_either() {
+        let config = config::Config {
+            default_duration: None,
+            remember_last_duration: false,
+            last_duration: Duration::from_secs(300),
+            ..config::Config::default()
+        };
+        assert_eq!(seeded_duration(&config), Duration::ZERO);
+    }
+
+    /// A ten minute countdown that was closed with five minutes left, a minute ago
+    fn closed_while_running(now: SystemTime) -> Vec<timer::Timer> {
+        let mut running = timer::Timer::default();
+        running.set_hms(Duration::from_secs(600));
+        running.duration = Duration::from_secs(600);
+        running.time = Duration::from_secs(300);
+        running.elapsed = Duration::from_secs(300);
+        running.state = timer::State::Running;
+        running.ticked_at = Some(now - Duration::from_secs(60));
+        vec![running, timer::Timer::default()]
+    }
+
+    #[test]
+    fn resuming_counts_the_time_since_closing() {
+        let now = SystemTime::now();
+        let mut timers = closed_while_running(now);
+        let interrupted = hold_interrupted(&mut timers, now.into());
+        assert_eq!(interrupted, [timers[0].id]);
+        assert_eq!(timers[0].state, timer::State::Stopped);
+
+        timers[0].catch_up(now);
+        assert_eq!(timers[0].state, timer::State::Running);
+        assert_eq!(timers[0].time, Duration::from_secs(240));
+    }
+
+    #[test]
+    fn discarding_brings_back_the_entered_duration() {
+        let now = SystemTime::now();
+        let mut timers = closed_while_running(now);
+        let interrupted = hold_interrupted(&mut timers, now.into());
+        assert_eq!(interrupted, [timers[0].id]);
+
+        timers[0].reset();
+        assert_eq!(timers[0].state, timer::State::Stopped);
+        assert_eq!(
+            timers[0].get_duration(timer::MAX_DURATION).unwrap(),
+            Duration::from_secs(600)
+        );
+    }
+
+    fn dismissing(after_finish: config::AfterFinish) -> config::Config {
+        config::Config {
+            hold_finished: false,
+            after_finish,
+            ..config::Config::default()
+        }
+    }
+
+    /// A ten minute countdown that ran out and is ringing
+    fn ringing() -> timer::Timer {
+        let mut timer = timer::Timer::default();
+        timer.set_hms(Duration::from_secs(600));
+        timer.start(timer::MAX_DURATION).unwrap();
+        let started = timer.last_tick.unwrap();
+        assert!(timer.tick(started + Duration::from_secs(600)));
+        timer
+    }
+
+    #[test]
+    fn a_timer_ringing_at_close_comes_back_stopped() {
+        scratch_dir();
+        let profile = "ringing-at-close";
+        let mut timers = vec![ringing()];
+        let config = dismissing(config::AfterFinish::Keep);
+        assert!(settle_on_close(&mut timers, Instant::now(), &config).is_empty());
+        write_timers(profile, &timers).unwrap();
+
+        let mut loaded = load_timers(profile);
+        assert!(hold_interrupted(&mut loaded, chrono::Local::now()).is_empty());
+        assert_eq!(loaded[0].state, timer::State::Stopped);
+        assert_eq!(
+            loaded[0].get_duration(timer::MAX_DURATION).unwrap(),
+            Duration::from_secs(600)
+        );
+    }
+
+    #[test]
+    fn a_timer_ringing_at_close_can_be_removed() {
+        let mut timers = vec![ringing(), timer::Timer::default()];
+        let config = dismissing(config::AfterFinish::Remove);
+        settle_on_close(&mut timers, Instant::now(), &config);
+        assert_eq!(timers.len(), 1);
+        assert_eq!(timers[0].state, timer::State::Stopped);
+    }
+
+    #[test]
+    fn held_timers_stay_done_across_a_close() {
+        let mut timers = vec![ringing()];
+        let config = config::Config {
+            hold_finished: true,
+            ..config::Config::default()
+        };
+        settle_on_close(&mut timers, Instant::now(), &config);
+        assert_eq!(timers[0].state, timer::State::Finished);
+    }
+
+    #[test]
+    fn a_countdown_running_out_at_close_goes_into_the_history() {
+        let mut timer = timer::Timer::default();
+        timer.set_hms(Duration::from_secs(5));
+        timer.start(timer::MAX_DURATION).unwrap();
+        let started = timer.last_tick.unwrap();
+        let mut timers = vec![timer];
+        let config = dismissing(config::AfterFinish::Keep);
 
-    fn save_state(&self, timers: &Vec<timer::Timer>) {
-        // Since I don't care about Windows
-        #[allow(deprecated)]
-        let mut path = std::env::home_dir().unwrap();
-        path.push(std::path::Path::new(".local/state/oxyclock/state.json"));
-        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
-        let file = File::create(path).unwrap();
-        let mut writer = BufWriter::new(file);
-        serde_json::to_writer(&mut writer, timers).unwrap();
-        writer.flush().unwrap();
+        let finished = settle_on_close(&mut timers, started + Duration::from_secs(5), &config);
+        assert_eq!(finished.len(), 1);
+        assert_eq!(finished[0].duration, Duration::from_secs(5));
+        assert_eq!(timers[0].state, timer::State::Stopped);
     }
 }
@@ -0,0 +1,102 @@
+#[cfg(feature = "mqtt")]
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::config;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Event {
+    Started,
+    Stopped,
+    Finished,
+}
+
+impl Event {
+    #[cfg_attr(not(feature = "mqtt"), allow(dead_code))]
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Started => "started",
+            Self::Stopped => "stopped",
+            Self::Finished => "finished",
+        }
+    }
+}
+
+/// Publishes timer events to the broker in `config.toml`. Builds without the
+/// `mqtt` feature never connect and drop every event.
+pub struct Publisher {
+    #[cfg(feature = "mqtt")]
+    client: rumqttc::Client,
+    #[cfg(feature = "mqtt")]
+    topic: String,
+}
+
+impl Publisher {
+    #[cfg(feature = "mqtt")]
+    pub fn connect(config: &config::Mqtt) -> Option<Self> {
+        let (host, port) = match config.broker.rsplit_once(':') {
+            Some((host, port)) => match port.parse() {
+                Ok(port) => (host, port),
+                Err(_) => {
+                    log::error!("invalid MQTT broker {}", config.broker);
+                    return None;
+                }
+            },
+            None => (config.broker.as_str(), 1883),
+        };
+        let mut options =
+            rumqttc::MqttOptions::new(format!("oxyclock-{}", std::process::id()), host, port);
+        options.set_keep_alive(Duration::from_secs(30));
+        if let Some(username) = &config.username {
+            options.set_credentials(username, config.password.clone().unwrap_or_default());
+        }
+
+        // The connection reconnects on its own as long as it keeps being polled,
+        // errors only slow the polling down so a missing broker doesn't spin
+        let (client, mut connection) = rumqttc::Client::new(options, 16);
+        std::thread::spawn(move || {
+            for notification in connection.iter() {
+                if let Err(err) = notification {
+                    log::warn!("MQTT connection failed: {err}");
+                    std::thread::sleep(Duration::from_secs(5));
+                }
+            }
+        });
+
+        Some(Self {
+            client,
+            topic: config.topic.clone(),
+        })
+    }
+
+    #[cfg(not(feature = "mqtt"))]
+    pub fn connect(config: &config::Mqtt) -> Option<Self> {
+        log::warn!(
+            "not connecting to {}, oxyclock was built without the mqtt feature",
+            config.broker
+        );
+        None
+    }
+
+    /// Queues the event without waiting for the broker, it's dropped when the
+    /// queue is full
+    #[cfg_attr(not(feature = "mqtt"), allow(unused_variables))]
+    pub fn publish(&self, id: Uuid, name: &str, event: Event) {
+        #[cfg(feature = "mqtt")]
+        {
+            let payload = serde_json::json!({
+                "id": id,
+                "name": name,
+                "event": event.as_str(),
+            });
+            if let Err(err) = self.client.try_publish(
+                &self.topic,
+                rumqttc::QoS::AtLeastOnce,
+                false,
+                payload.to_string(),
+            ) {
+                log::warn!("failed to publish {} to MQTT: {err}", event.as_str());
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use chrono::{DateTime, Datelike, Local};
+use std::time::Duration;
+
+use crate::{history::Record, i18n::t, timer::Mode};
+
+/// Aggregated focus time, computed once when the dashboard is opened
+pub struct Summary {
+    pub today: Duration,
+    pub week: Duration,
+    /// Countdowns run to completion today. Stopped stopwatches are focus time but
+    /// don't count toward the goal, there was no end to reach.
+    pub finished_today: u32,
+    /// Totals per timer name, largest first. Timers have no tags, the name is what
+    /// tells runs apart.
+    pub by_name: Vec<(String, Duration)>,
+}
+
+pub fn summarize(records: &[Record], now: DateTime<Local>) -> Summary {
+    let mut summary = Summary {
+        today: Duration::from_secs(0),
+        week: Duration::from_secs(0),
+        finished_today: 0,
+        by_name: Vec::new(),
+    };
+
+    for record in records {
+        let finished_at: DateTime<Local> = record.finished_at.into();
+        if finished_at.date_naive() == now.date_naive() {
+            summary.today += record.duration;
+            if record.mode == Mode::Countdown {
+                summary.finished_today += 1;
+            }
+        }
+        if finished_at.iso_week() == now.iso_week() {
+            summary.week += record.duration;
+        }
+
+        // Cards number unnamed timers by position, which history doesn't keep
+        let name = if record.name.is_empty() {
+            t("unnamed_timers")
+        } else {
+            &record.name
+        };
+        match summary.by_name.iter_mut().find(|(n, _)| n == name) {
+            Some((_, total)) => *total += record.duration,
+            None => summary.by_name.push((name.to_string(), record.duration)),
+        }
+    }
+
+    summary.by_name.sort_by(|a, b| b.1.cmp(&a.1));
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::SystemTime;
+
+    fn record(mode: Mode) -> Record {
+        Record {
+            name: "Focus".to_string(),
+            duration: Duration::from_secs(1500),
+            finished_at: SystemTime::now(),
+            mode,
+        }
+    }
+
+    #[test]
+    fn stopped_stopwatches_dont_count_toward_the_goal() {
+        let summary = summarize(&[record(Mode::Stopwatch)], Local::now());
+        assert_eq!(summary.finished_today, 0);
+        assert_eq!(summary.today, Duration::from_secs(1500));
+    }
+
+    #[test]
+    fn finished_countdowns_count_toward_the_goal() {
+        let records = [record(Mode::Countdown), record(Mode::Stopwatch)];
+        let summary = summarize(&records, Local::now());
+        assert_eq!(summary.finished_today, 1);
+        assert_eq!(summary.today, Duration::from_secs(3000));
+    }
+}
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Write},
+    time::Duration,
+};
+use uuid::Uuid;
+
+use crate::{timer::Timer, utils};
+
+/// A reusable timer setup, kept in `templates.json` and copied into new timers.
+/// It carries the name, duration, notification and sound switches and the note.
+/// Any other timer setting isn't part of it, timers made from it start with the
+/// defaults for those.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Template {
+    pub id: Uuid,
+    pub name: String,
+    pub duration: Duration,
+    pub notify: bool,
+    pub silent: bool,
+    #[serde(default)]
+    pub note: String,
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            name: String::new(),
+            duration: Duration::from_secs(25 * 60),
+            notify: true,
+            silent: false,
+            note: String::new(),
+        }
+    }
+}
+
+impl Template {
+    /// A stopped timer with every field of the template and a fresh id
+    pub fn instantiate(&self) -> Timer {
+        let mut timer = Timer::new(Uuid::new_v4());
+        timer.name = self.name.clone();
+        timer.set_hms(self.duration);
+        timer.notify = self.notify;
+        timer.silent = self.silent;
+        timer.note = self.note.clone();
+        timer
+    }
+}
+
+pub fn load() -> Vec<Template> {
+    let path = utils::state_dir().join("templates.json");
+    let Ok(file) = File::open(&path) else {
+        return Vec::new();
+    };
+    serde_json::from_reader(BufReader::new(file)).unwrap_or_else(|err| {
+        log::error!("failed to parse {}: {err}", path.display());
+        Vec::new()
+    })
+}
+
+pub fn save(templates: &[Template]) -> io::Result<()> {
+    std::fs::create_dir_all(utils::state_dir())?;
+    let file = File::create(utils::state_dir().join("templates.json"))?;
+    let mut writer = BufWriter::new(file);
+    serde_json::to_writer_pretty(&mut writer, templates)?;
+    writer.flush()
+}
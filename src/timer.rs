@@ -1,9 +1,16 @@
-use iced::Subscription;
+use chrono::{DateTime, Datelike, Local, TimeZone};
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
 
-use crate::{utils, Msg};
+use crate::i18n::{t, t_fmt};
+
+const SHARE_PREFIX: &str = "oxyclock:";
+/// The hours field doesn't go past three digits, whatever the configured cap
+pub const MAX_DURATION: Duration = Duration::from_secs(999 * 3600 + 59 * 60 + 59);
+/// How far the wall clock may drift from the monotonic one between two ticks
+/// before it counts as changed
+const CLOCK_JUMP: Duration = Duration::from_secs(2);
 
 #[derive(Debug, Clone, Serialize, Deserialize, Hash)]
 pub struct Timer {
@@ -15,15 +22,140 @@ pub struct Timer {
     pub hours: String,
     pub minutes: String,
     pub seconds: String,
+    /// What the fields held when the timer was last started fresh, restored on reset
+    #[serde(default)]
+    pub duration: Duration,
+    /// Show a desktop notification when the timer finishes
+    #[serde(default = "default_true")]
+    pub notify: bool,
+    /// Visual-only timer: no sound and no desktop notification
+    #[serde(default)]
+    pub silent: bool,
+    #[serde(default)]
+    pub note: String,
+    /// Break started automatically once the timer finishes
+    #[serde(default)]
+    pub after_finish: Option<Duration>,
+    /// Plays of the alarm sound, `Config::alarm_repeats` applies when unset
+    #[serde(default)]
+    pub alarm_repeats: Option<u32>,
+    /// Counting down the break rather than the timer itself
+    #[serde(default)]
+    pub on_break: bool,
+    /// Listed before unpinned timers whatever the sort order
+    #[serde(default)]
+    pub pinned: bool,
+    /// URL that gets a POST when the timer finishes, needs the `webhook` feature
+    #[serde(default)]
+    pub webhook: Option<String>,
+    /// Remaining times to speak out, the configured defaults apply when unset
+    #[serde(default)]
+    pub announce_at: Option<Vec<Duration>>,
+    /// Emoji shown on the card and in front of the name in notifications, or
+    /// the path of an image the notification shows instead
+    #[serde(default)]
+    pub icon: Option<String>,
+    /// Days and time the timer goes off every week, it counts down to the next
+    /// occurrence instead of its fields
+    #[serde(default)]
+    pub schedule: Option<WeeklySchedule>,
+    #[serde(default)]
+    pub mode: Mode,
+    /// Stopwatch time at each lap, oldest first
+    #[serde(default)]
+    pub laps: Vec<Duration>,
+    /// Timers saved before this was tracked count as created when loaded
+    #[serde(default = "SystemTime::now")]
+    pub created_at: SystemTime,
+    /// Wall-clock time of the last tick, tells how long a timer that was running
+    /// when the app closed has been left alone
+    #[serde(default)]
+    pub ticked_at: Option<SystemTime>,
+    #[serde(skip)]
+    pub error: Option<String>,
+    /// When `time` was last brought up to date, ticks are measured from here so
+    /// late or coarse wake-ups don't drift
+    #[serde(skip)]
+    pub last_tick: Option<Instant>,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash)]
 pub enum State {
     Running,
     NotificationSound,
+    /// Ran out and waits for the user to dismiss it
+    Finished,
     Stopped,
 }
 
+/// Weekdays and a local time of day, like weekdays at 09:00
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Hash)]
+pub struct WeeklySchedule {
+    /// Monday first
+    pub days: [bool; 7],
+    /// Minutes after midnight
+    pub at: u32,
+}
+
+impl Default for WeeklySchedule {
+    fn default() -> Self {
+        Self {
+            days: [false; 7],
+            at: 9 * 60,
+        }
+    }
+}
+
+impl WeeklySchedule {
+    /// First occurrence strictly after `now`, today's only if it's still ahead.
+    /// `None` when no day is picked. A time skipped by a DST change moves to the
+    /// next matching day.
+    pub fn next_after(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        // Eight days, so today comes around again when its time already passed
+        (0..8).find_map(|offset| {
+            let date = now.date_naive() + chrono::Days::new(offset);
+            if !self.days[date.weekday().num_days_from_monday() as usize] {
+                return None;
+            }
+            let time = date.and_hms_opt(self.at / 60 % 24, self.at % 60, 0)?;
+            Local
+                .from_local_datetime(&time)
+                .earliest()
+                .filter(|at| *at > now)
+        })
+    }
+}
+
+/// What `time` holds while the timer runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize, Hash)]
+pub enum Mode {
+    /// The time left, the timer finishes and alarms at zero
+    #[default]
+    Countdown,
+    /// The time counted so far, it runs until stopped and never finishes
+    Stopwatch,
+}
+
+/// Why `Timer::start` left a countdown stopped
+#[derive(Debug)]
+pub enum StartError {
+    /// The fields are all zero, it would finish on the first tick
+    NoDuration,
+    /// The fields don't hold a duration, or one past the maximum
+    Invalid(Box<dyn std::error::Error>),
+}
+
+impl std::fmt::Display for StartError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDuration => write!(f, "{}", t("set_duration_first")),
+            Self::Invalid(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for StartError {}
+
 impl Timer {
     pub fn new(id: Uuid) -> Self {
         Self {
@@ -35,6 +167,24 @@ impl Timer {
             hours: String::from("00"),
             minutes: String::from("00"),
             seconds: String::from("00"),
+            duration: Duration::from_secs(0),
+            notify: true,
+            silent: false,
+            note: String::new(),
+            after_finish: None,
+            alarm_repeats: None,
+            on_break: false,
+            pinned: false,
+            webhook: None,
+            announce_at: None,
+            icon: None,
+            schedule: None,
+            mode: Mode::Countdown,
+            laps: Vec::new(),
+            created_at: SystemTime::now(),
+            ticked_at: None,
+            error: None,
+            last_tick: None,
         }
     }
 
@@ -47,45 +197,925 @@ impl Timer {
         self.seconds = format!("{:02}", elapsed);
     }
 
-    pub fn get_duration(&self) -> Result<Duration, Box<dyn std::error::Error>> {
-        let hours_to_secs = self.hours.parse::<u64>()? * 3600;
-        let minutes_to_secs = self.minutes.parse::<u64>()? * 60;
-        let seconds = self.seconds.parse::<u64>()?;
-        let total_secs = hours_to_secs + minutes_to_secs + seconds;
+    /// Advances a running timer by the time passed since the previous tick. Returns true
+    /// exactly once per run, on the tick that finishes it, since the state leaves
+    /// `Running` right away and bunched-up ticks arriving afterwards are ignored.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        if self.state != State::Running {
+            return false;
+        }
+
+        let tick = self
+            .last_tick
+            .map_or(Duration::ZERO, |last| now.saturating_duration_since(last));
+        self.last_tick = Some(now);
+        self.ticked_at = Some(SystemTime::now());
+
+        if self.mode == Mode::Stopwatch {
+            self.time = self.time.saturating_add(tick);
+            self.elapsed += tick;
+            return false;
+        }
+
+        if self.time <= tick {
+            self.elapsed += self.time;
+            self.time = Duration::from_secs(0);
+            self.update_elapsed_hms();
+            self.state = State::NotificationSound;
+            return true;
+        }
+
+        self.time -= tick;
+        self.elapsed += tick;
+        false
+    }
+
+    /// Counts down to the next occurrence of the schedule. Returns false, leaving
+    /// the timer as it is, without a schedule or a day picked.
+    pub fn arm_schedule(&mut self, now: DateTime<Local>) -> bool {
+        let Some(next) = self.schedule.as_ref().and_then(|s| s.next_after(now)) else {
+            return false;
+        };
+
+        self.state = State::Running;
+        self.time = (next - now).to_std().unwrap_or_default();
+        self.elapsed = Duration::from_secs(0);
+        self.error = None;
+        self.on_break = false;
+        self.last_tick = Some(Instant::now());
+        self.ticked_at = Some(now.into());
+        true
+    }
+
+    /// Whether the wall clock moved by more or less than the monotonic one since the
+    /// previous tick, because it was set by hand, synced or the system slept. The
+    /// countdown itself goes by the monotonic clock and isn't affected.
+    pub fn clock_jumped(&self, now: Instant, wall: SystemTime) -> bool {
+        if self.state != State::Running {
+            return false;
+        }
+        let (Some(last_tick), Some(ticked_at)) = (self.last_tick, self.ticked_at) else {
+            return false;
+        };
+        let monotonic = now.saturating_duration_since(last_tick);
+        let drift = match wall.duration_since(ticked_at) {
+            Ok(passed) if passed > monotonic => passed - monotonic,
+            Ok(passed) => monotonic - passed,
+            // Set back
+            Err(err) => err.duration() + monotonic,
+        };
+        drift > CLOCK_JUMP
+    }
+
+    /// Resumes a timer that was running when the app closed, counting the time since
+    /// its last tick as passed. A countdown that ran out meanwhile finishes on its
+    /// next tick.
+    pub fn catch_up(&mut self, now: SystemTime) {
+        let away = self
+            .ticked_at
+            .and_then(|at| now.duration_since(at).ok())
+            .unwrap_or_default();
+        let passed = match self.mode {
+            Mode::Countdown => away.min(self.time),
+            Mode::Stopwatch => away,
+        };
+        match self.mode {
+            Mode::Countdown => self.time -= passed,
+            Mode::Stopwatch => self.time = self.time.saturating_add(passed),
+        }
+        self.elapsed += passed;
+        self.state = State::Running;
+        self.last_tick = Some(Instant::now());
+        self.ticked_at = Some(now);
+    }
+
+    /// Re-arms a just finished timer for its break, if it has one and wasn't
+    /// already on it. Returns whether the break started.
+    pub fn start_break(&mut self) -> bool {
+        let Some(length) = self.after_finish.filter(|_| !self.on_break) else {
+            self.on_break = false;
+            return false;
+        };
+
+        self.on_break = true;
+        self.state = State::Running;
+        self.time = length;
+        self.elapsed = Duration::from_secs(0);
+        self.last_tick = Some(Instant::now());
+        true
+    }
+
+    /// Unnamed timers are shown by their position in the list, `name` stays
+    /// empty so the fallback follows reorders and deletions
+    pub fn display_name(&self, position: usize) -> String {
+        if self.name.is_empty() {
+            t_fmt("unnamed_timer", &[("number", &(position + 1).to_string())])
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// Parses the fields, rejecting totals above `max`
+    pub fn get_duration(&self, max: Duration) -> Result<Duration, Box<dyn std::error::Error>> {
+        let max = max.min(MAX_DURATION);
+        let hours = parse_field("hours_not_a_number", &self.hours)?;
+        let minutes = parse_field("minutes_not_a_number", &self.minutes)?;
+        let seconds = parse_field("seconds_not_a_number", &self.seconds)?;
+        let total_secs = total_secs(hours, minutes, seconds)
+            .filter(|&secs| secs <= max.as_secs())
+            .ok_or_else(|| t_fmt("over_max", &[("max", &format_hms(max))]))?;
 
         Ok(Duration::from_secs(total_secs))
     }
 
     pub fn time_to_hms_string(&self) -> (String, String, String) {
-        let total_secs = self.time.as_secs();
-        let hours = format!("{:02}", total_secs / 3600);
-        let minutes = format!("{:02}", (total_secs % 3600) / 60);
-        let seconds = format!("{:02}", total_secs % 60);
-
-        (hours, minutes, seconds)
-    }
-
-    pub fn subscription(&self) -> Subscription<Msg> {
-        println!("SUBSCRIPTION. STATE: {:?}", self.state);
-        match self.state {
-            State::Running => iced::time::every(Duration::from_secs(1))
-                .with(self.id)
-                .map(|s| Msg::Tick(s.0)),
-            State::NotificationSound => {
-                std::thread::spawn(|| {
-                    if let Err(err) = utils::play_notification_sound() {
-                        eprintln!("failed to play notification sound: {err}");
-                    }
-                });
-                Subscription::none()
-            }
-            State::Stopped => Subscription::none(),
+        hms_strings(self.time)
+    }
+
+    /// Stopped with time left on the clock, starting again resumes the countdown
+    pub fn is_paused(&self) -> bool {
+        self.state == State::Stopped && !self.time.is_zero()
+    }
+
+    /// Whether the fields hold a duration `start` accepts that isn't zero.
+    /// Stopwatches need nothing to count up from.
+    pub fn can_start(&self, max: Duration) -> bool {
+        self.mode == Mode::Stopwatch
+            || self
+                .get_duration(max)
+                .is_ok_and(|duration| !duration.is_zero())
+    }
+
+    /// Starts counting down the fields, or resumes a paused countdown. Returns the
+    /// duration of this run.
+    pub fn start(&mut self, max: Duration) -> Result<Duration, StartError> {
+        // `elapsed` is the stretch since this start either way, what a stop records
+        if self.mode == Mode::Stopwatch {
+            self.state = State::Running;
+            self.elapsed = Duration::from_secs(0);
+            self.error = None;
+            self.on_break = false;
+            self.last_tick = Some(Instant::now());
+            self.ticked_at = Some(SystemTime::now());
+            return Ok(self.time);
+        }
+
+        let duration = self.get_duration(max).map_err(StartError::Invalid)?;
+        // It would finish on the first tick with a pointless alarm
+        if duration.is_zero() {
+            return Err(StartError::NoDuration);
         }
+        // Resuming keeps the duration of the original run for resets
+        if !self.is_paused() {
+            self.duration = duration;
+        }
+        self.state = State::Running;
+        self.time = duration;
+        self.elapsed = Duration::from_secs(0);
+        self.error = None;
+        self.on_break = false;
+        self.last_tick = Some(Instant::now());
+        self.ticked_at = Some(SystemTime::now());
+        Ok(duration)
+    }
+
+    /// Stops the countdown, leaving the time left in the fields
+    pub fn pause(&mut self) {
+        self.state = State::Stopped;
+        self.update_elapsed_hms();
+    }
+
+    /// Records the time a running stopwatch is at
+    pub fn lap(&mut self) {
+        if self.mode == Mode::Stopwatch && self.state == State::Running {
+            self.laps.push(self.time);
+        }
+    }
+
+    /// Stops the timer and brings back the fields as they were typed in
+    pub fn reset(&mut self) {
+        self.state = State::Stopped;
+        self.laps.clear();
+        self.on_break = false;
+        self.time = Duration::from_secs(0);
+        self.elapsed = Duration::from_secs(0);
+        self.set_hms(self.duration);
+    }
+
+    pub fn set_hms(&mut self, duration: Duration) {
+        (self.hours, self.minutes, self.seconds) = hms_strings(duration);
+    }
+
+    pub fn remaining_percentage(&self) -> u64 {
+        let total_secs = self.run_length().as_secs();
+        if total_secs == 0 {
+            return 0;
+        }
+
+        self.time.as_secs() * 100 / total_secs
+    }
+
+    /// What the current run counts down from. `elapsed` starts over on a resume, so
+    /// it's the duration of the run, or the break. Schedules and timers saved before
+    /// the duration was kept fall back to what the run counted so far.
+    fn run_length(&self) -> Duration {
+        let run = if self.on_break {
+            self.after_finish
+        } else {
+            Some(self.duration)
+        };
+        run.filter(|run| !run.is_zero() && *run >= self.time)
+            .unwrap_or(self.time + self.elapsed)
+    }
+
+    pub fn to_share_string(&self) -> Result<String, Box<dyn std::error::Error>> {
+        let total_secs = self.get_duration(MAX_DURATION)?.as_secs();
+        Ok(format!(
+            "{SHARE_PREFIX}{}:{:02}:{:02}:{:02}",
+            self.name,
+            total_secs / 3600,
+            (total_secs % 3600) / 60,
+            total_secs % 60
+        ))
+    }
+
+    pub fn from_share_string(value: &str) -> Option<Self> {
+        let rest = value.trim().strip_prefix(SHARE_PREFIX)?;
+        let parts: Vec<&str> = rest.rsplitn(4, ':').collect();
+        // Both NAME:HH:MM:SS and the shorter NAME:MM:SS are accepted
+        let (name, hours, minutes, seconds) = match parts.as_slice() {
+            [s, m, h, name] if h.parse::<u64>().is_ok() => (name.to_string(), *h, *m, *s),
+            [s, m, h, name] => (format!("{name}:{h}"), "0", *m, *s),
+            [s, m, name] => (name.to_string(), "0", *m, *s),
+            _ => return None,
+        };
+        // Pasted text can hold anything, it gets no further than the fields would
+        let total_secs = total_secs(
+            hours.parse().ok()?,
+            minutes.parse().ok()?,
+            seconds.parse().ok()?,
+        )
+        .filter(|&secs| secs <= MAX_DURATION.as_secs())?;
+
+        let mut timer = Self {
+            name,
+            ..Self::default()
+        };
+        timer.set_hms(Duration::from_secs(total_secs));
+        Some(timer)
     }
 }
 
+/// Counts the running scheduled timers down to their next occurrence again, after
+/// the wall clock changed under them. Returns whether any was re-armed.
+pub fn rearm_schedules(timers: &mut [Timer], now: DateTime<Local>) -> bool {
+    let mut rearmed = false;
+    for timer in timers
+        .iter_mut()
+        .filter(|timer| timer.state == State::Running)
+    {
+        rearmed |= timer.arm_schedule(now);
+    }
+    rearmed
+}
+
+/// `duration` to the nearest multiple of `step`, halfway rounds up. Zero stays zero
+/// and anything else rounds to at least one step.
+pub fn round_to(duration: Duration, step: Duration) -> Duration {
+    let step = step.as_secs();
+    if step == 0 || duration.is_zero() {
+        return duration;
+    }
+    let steps = ((duration.as_secs() + step / 2) / step).max(1);
+    Duration::from_secs(steps * step)
+}
+
+/// The smallest of `milestones` passed when the time left went from `before` to
+/// `after`. Each one is passed once per run since the time left only goes down.
+pub fn crossed(before: Duration, after: Duration, milestones: &[Duration]) -> Option<Duration> {
+    milestones
+        .iter()
+        .copied()
+        .filter(|&milestone| after <= milestone && milestone < before)
+        .min()
+}
+
+/// Hours, minutes and seconds of a digit stream typed like on a microwave: digits
+/// fill from the right, so "130" is 1:30 and "1000" is 10:00. Anything but digits
+/// is skipped, which lets the stream be the formatted fields plus what was typed.
+/// Minutes and seconds aren't capped, "90" stays 90 seconds.
+pub fn shift_digits(input: &str) -> (String, String, String) {
+    let digits: String = input.chars().filter(char::is_ascii_digit).collect();
+    let digits = digits.trim_start_matches('0');
+    // Three hour digits like the fields, older digits fall off the left
+    let digits = format!("{digits:0>6}");
+    let digits = &digits[digits.len().saturating_sub(7)..];
+    let (rest, seconds) = digits.split_at(digits.len() - 2);
+    let (hours, minutes) = rest.split_at(rest.len() - 2);
+    let hours = hours.parse::<u64>().unwrap_or(0);
+
+    (
+        format!("{hours:02}"),
+        minutes.to_string(),
+        seconds.to_string(),
+    )
+}
+
+/// Names told apart in notifications and the tray, so "Tea" and " tea" collide.
+/// Unnamed timers never do, they're shown by position.
+pub fn same_name(a: &str, b: &str) -> bool {
+    let a = a.trim();
+    !a.is_empty() && a.to_lowercase() == b.trim().to_lowercase()
+}
+
+/// `name` with the first free " (2)", " (3)"... suffix when another timer
+/// already has it
+pub fn unique_name(name: &str, others: &[&str]) -> String {
+    let taken = |candidate: &str| others.iter().any(|other| same_name(candidate, other));
+    if !taken(name) {
+        return name.to_string();
+    }
+    (2..)
+        .map(|number| format!("{} ({number})", name.trim()))
+        .find(|candidate| !taken(candidate))
+        .unwrap()
+}
+
+/// `HH:MM:SS`, with as many hour digits as needed
+pub fn format_hms(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// `error` is the message for a field that isn't a number, with `{value}` in it
+fn parse_field(error: &'static str, value: &str) -> Result<u64, String> {
+    value
+        .trim()
+        .parse::<u64>()
+        .map_err(|_| t_fmt(error, &[("value", value)]))
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn hms_strings(duration: Duration) -> (String, String, String) {
+    let total_secs = duration.as_secs();
+    let hours = format!("{:02}", total_secs / 3600);
+    let minutes = format!("{:02}", (total_secs % 3600) / 60);
+    let seconds = format!("{:02}", total_secs % 60);
+
+    (hours, minutes, seconds)
+}
+
 impl Default for Timer {
     fn default() -> Self {
         Self::new(uuid::Uuid::new_v4())
     }
 }
+
+/// Seconds in `hours`, `minutes` and `seconds`, `None` when that overflows
+fn total_secs(hours: u64, minutes: u64, seconds: u64) -> Option<u64> {
+    hours
+        .checked_mul(3600)?
+        .checked_add(minutes.checked_mul(60)?)?
+        .checked_add(seconds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timer(hours: &str, minutes: &str, seconds: &str) -> Timer {
+        Timer {
+            hours: hours.to_string(),
+            minutes: minutes.to_string(),
+            seconds: seconds.to_string(),
+            ..Timer::default()
+        }
+    }
+
+    /// Starts the timer and returns the instant its ticks are measured from
+    fn start(timer: &mut Timer) -> Instant {
+        timer.start(MAX_DURATION).unwrap();
+        timer.last_tick.unwrap()
+    }
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn start_counts_down_the_fields() {
+        let mut timer = timer("00", "01", "30");
+        assert_eq!(timer.start(MAX_DURATION).unwrap(), secs(90));
+        assert_eq!(timer.state, State::Running);
+        assert_eq!(timer.time, secs(90));
+        assert_eq!(timer.duration, secs(90));
+    }
+
+    #[test]
+    fn tick_counts_the_time_since_the_previous_tick() {
+        let mut timer = timer("00", "01", "30");
+        let started = start(&mut timer);
+        assert!(!timer.tick(started + secs(10)));
+        assert!(!timer.tick(started + secs(25)));
+        assert_eq!(timer.time, secs(65));
+        assert_eq!(timer.elapsed, secs(25));
+        assert_eq!(timer.state, State::Running);
+    }
+
+    #[test]
+    fn tick_finishes_at_zero() {
+        let mut timer = timer("00", "01", "30");
+        let started = start(&mut timer);
+        assert!(timer.tick(started + secs(95)));
+        assert_eq!(timer.state, State::NotificationSound);
+        assert_eq!(timer.time, Duration::ZERO);
+        assert_eq!(timer.elapsed, secs(90));
+    }
+
+    #[test]
+    fn bunched_up_ticks_finish_once() {
+        let mut timer = timer("00", "00", "01");
+        let started = start(&mut timer);
+        let finished = [secs(2), secs(2), secs(3)]
+            .into_iter()
+            .filter(|&at| timer.tick(started + at))
+            .count();
+        assert_eq!(finished, 1);
+    }
+
+    #[test]
+    fn tick_ignores_stopped_timers() {
+        let mut timer = timer("00", "01", "30");
+        assert!(!timer.tick(Instant::now() + secs(100)));
+        assert_eq!(timer.time, Duration::ZERO);
+        assert_eq!(timer.state, State::Stopped);
+    }
+
+    #[test]
+    fn pause_leaves_the_time_left_in_the_fields() {
+        let mut timer = timer("00", "01", "30");
+        let started = start(&mut timer);
+        timer.tick(started + secs(30));
+        timer.pause();
+        assert_eq!(timer.state, State::Stopped);
+        assert!(timer.is_paused());
+        assert_eq!(
+            (
+                timer.hours.as_str(),
+                timer.minutes.as_str(),
+                timer.seconds.as_str()
+            ),
+            ("00", "01", "00")
+        );
+
+        // Resuming runs what was left but remembers the original duration
+        assert_eq!(timer.start(MAX_DURATION).unwrap(), secs(60));
+        assert_eq!(timer.duration, secs(90));
+    }
+
+    #[test]
+    fn reset_stops_the_timer() {
+        let mut timer = timer("00", "00", "10");
+        let started = start(&mut timer);
+        timer.tick(started + secs(4));
+        timer.reset();
+        assert_eq!(timer.state, State::Stopped);
+        assert_eq!(timer.time, Duration::ZERO);
+        assert_eq!(timer.elapsed, Duration::ZERO);
+        assert!(!timer.is_paused());
+    }
+
+    #[test]
+    fn reset_brings_back_the_entered_duration() {
+        let mut timer = timer("00", "25", "00");
+        let started = start(&mut timer);
+        timer.tick(started + secs(61));
+        timer.pause();
+        assert_eq!(timer.minutes, "23");
+
+        timer.reset();
+        assert_eq!(
+            (
+                timer.hours.as_str(),
+                timer.minutes.as_str(),
+                timer.seconds.as_str()
+            ),
+            ("00", "25", "00")
+        );
+        assert_eq!(timer.start(MAX_DURATION).unwrap(), secs(25 * 60));
+    }
+
+    #[test]
+    fn reset_after_finishing_brings_back_the_entered_duration() {
+        let mut timer = timer("00", "00", "30");
+        let started = start(&mut timer);
+        assert!(timer.tick(started + secs(30)));
+        assert_eq!(timer.seconds, "00");
+
+        timer.reset();
+        assert_eq!(timer.seconds, "30");
+    }
+
+    #[test]
+    fn get_duration_adds_up_the_fields() {
+        assert_eq!(
+            timer("01", "02", "03").get_duration(MAX_DURATION).unwrap(),
+            secs(3723)
+        );
+        // Fields aren't capped on their own, only the total is
+        assert_eq!(
+            timer("0", "90", " 5 ").get_duration(MAX_DURATION).unwrap(),
+            secs(5405)
+        );
+    }
+
+    #[test]
+    fn starting_with_letters_in_a_field_explains_why_not() {
+        let mut timer = timer("00", "ab", "00");
+        let err = timer.start(MAX_DURATION).unwrap_err().to_string();
+        assert!(err.contains("minutes"), "{err}");
+        assert!(err.contains("\"ab\""), "{err}");
+        assert_eq!(timer.state, State::Stopped);
+        assert!(!timer.can_start(MAX_DURATION));
+    }
+
+    #[test]
+    fn get_duration_respects_the_configured_maximum() {
+        let timer = timer("02", "00", "01");
+        assert!(timer.get_duration(secs(2 * 3600)).is_err());
+        assert_eq!(timer.get_duration(secs(3 * 3600)).unwrap(), secs(7201));
+    }
+
+    #[test]
+    fn get_duration_at_and_around_the_cap() {
+        let cap = secs(24 * 3600);
+        assert_eq!(
+            timer("23", "59", "59").get_duration(cap).unwrap(),
+            secs(86399)
+        );
+        assert_eq!(timer("24", "00", "00").get_duration(cap).unwrap(), cap);
+        let err = timer("24", "00", "01").get_duration(cap).unwrap_err();
+        assert!(err.to_string().contains("24:00:00"), "{err}");
+    }
+
+    #[test]
+    fn get_duration_rejects_overflowing_fields() {
+        for (hours, minutes, seconds) in [
+            ("9999999999999999999", "00", "00"),
+            ("18446744073709551615", "00", "00"),
+            ("00", "18446744073709551615", "00"),
+            ("00", "00", "18446744073709551615"),
+            ("5124095576030431", "00", "00"),
+        ] {
+            let result = timer(hours, minutes, seconds).get_duration(MAX_DURATION);
+            assert!(result.is_err(), "{hours}:{minutes}:{seconds} was accepted");
+        }
+        assert!(timer("-1", "00", "00").get_duration(MAX_DURATION).is_err());
+    }
+
+    #[test]
+    fn get_duration_accepts_the_largest_duration() {
+        assert_eq!(
+            timer("999", "59", "59").get_duration(MAX_DURATION).unwrap(),
+            MAX_DURATION
+        );
+        assert!(timer("999", "59", "60").get_duration(MAX_DURATION).is_err());
+        // However high the configured cap, the fields can't go past three hour digits
+        assert!(timer("1000", "00", "00")
+            .get_duration(Duration::MAX)
+            .is_err());
+    }
+
+    #[test]
+    fn durations_past_99_hours_round_trip() {
+        let duration = secs(123 * 3600 + 4 * 60 + 5);
+        assert_eq!(format_hms(duration), "123:04:05");
+
+        let mut timer = Timer::default();
+        timer.set_hms(duration);
+        assert_eq!(
+            (
+                timer.hours.as_str(),
+                timer.minutes.as_str(),
+                timer.seconds.as_str()
+            ),
+            ("123", "04", "05")
+        );
+        assert_eq!(timer.get_duration(MAX_DURATION).unwrap(), duration);
+
+        timer.time = duration;
+        timer.update_elapsed_hms();
+        assert_eq!(timer.hours, "123");
+        assert_eq!(
+            timer.time_to_hms_string(),
+            ("123".to_string(), "04".to_string(), "05".to_string())
+        );
+    }
+
+    #[test]
+    fn unnamed_timers_go_by_position() {
+        let mut timer = Timer::default();
+        assert_eq!(timer.display_name(2), "Timer 3");
+        timer.name = "Tea".to_string();
+        assert_eq!(timer.display_name(2), "Tea");
+    }
+
+    #[test]
+    fn share_string_round_trips() {
+        let shared = Timer {
+            name: "Tea: green".to_string(),
+            ..timer("1", "2", "75")
+        };
+        let share = shared.to_share_string().unwrap();
+        assert_eq!(share, "oxyclock:Tea: green:01:03:15");
+
+        let pasted = Timer::from_share_string(&share).unwrap();
+        assert_eq!(pasted.name, "Tea: green");
+        assert_eq!(
+            (
+                pasted.hours.as_str(),
+                pasted.minutes.as_str(),
+                pasted.seconds.as_str()
+            ),
+            ("01", "03", "15")
+        );
+        assert_ne!(pasted.id, shared.id);
+    }
+
+    #[test]
+    fn share_string_without_hours() {
+        let pasted = Timer::from_share_string("  oxyclock:Eggs:07:30\n").unwrap();
+        assert_eq!(pasted.name, "Eggs");
+        assert_eq!(pasted.get_duration(MAX_DURATION).unwrap(), secs(450));
+    }
+
+    #[test]
+    fn share_string_rejects_other_text() {
+        assert!(Timer::from_share_string("Tea:00:05:00").is_none());
+        assert!(Timer::from_share_string("oxyclock:Tea:five:00").is_none());
+    }
+
+    #[test]
+    fn can_start_needs_a_duration_above_zero() {
+        assert!(!timer("00", "00", "00").can_start(MAX_DURATION));
+        assert!(!timer("00", "ab", "00").can_start(MAX_DURATION));
+        assert!(timer("00", "00", "01").can_start(MAX_DURATION));
+    }
+
+    #[test]
+    fn stopwatches_can_always_start() {
+        let stopwatch = Timer {
+            mode: Mode::Stopwatch,
+            ..timer("00", "00", "00")
+        };
+        assert!(stopwatch.can_start(MAX_DURATION));
+    }
+
+    #[test]
+    fn share_string_rejects_overflowing_fields() {
+        assert!(Timer::from_share_string("oxyclock:x:99999999999999999:00:00").is_none());
+        assert!(Timer::from_share_string("oxyclock:x:00:18446744073709551615:00").is_none());
+    }
+
+    #[test]
+    fn share_string_respects_the_maximum() {
+        assert!(Timer::from_share_string("oxyclock:x:999:59:59").is_some());
+        assert!(Timer::from_share_string("oxyclock:x:999:59:60").is_none());
+        assert!(Timer::from_share_string("oxyclock:x:1000:00:00").is_none());
+    }
+
+    #[test]
+    fn remaining_percentage_survives_a_pause() {
+        let mut timer = timer("00", "01", "40");
+        let started = start(&mut timer);
+        timer.tick(started + secs(50));
+        assert_eq!(timer.remaining_percentage(), 50);
+
+        timer.pause();
+        let resumed = start(&mut timer);
+        timer.tick(resumed + secs(25));
+        assert_eq!(timer.remaining_percentage(), 25);
+    }
+
+    #[test]
+    fn remaining_percentage_of_a_break_goes_by_the_break() {
+        let mut timer = Timer {
+            after_finish: Some(secs(40)),
+            ..timer("00", "01", "40")
+        };
+        let started = start(&mut timer);
+        assert!(timer.tick(started + secs(100)));
+        assert!(timer.start_break());
+        assert_eq!(timer.remaining_percentage(), 100);
+        let break_started = timer.last_tick.unwrap();
+        timer.tick(break_started + secs(10));
+        assert_eq!(timer.remaining_percentage(), 75);
+    }
+
+    #[test]
+    fn round_to_rounds_halfway_up() {
+        assert_eq!(round_to(secs(450), secs(300)), secs(600));
+        assert_eq!(round_to(secs(450), secs(60)), secs(480));
+        assert_eq!(round_to(secs(420), secs(300)), secs(300));
+    }
+
+    #[test]
+    fn round_to_keeps_zero_and_rounds_short_ones_up_to_a_step() {
+        assert_eq!(round_to(Duration::ZERO, secs(300)), Duration::ZERO);
+        assert_eq!(round_to(secs(20), secs(300)), secs(300));
+        assert_eq!(round_to(secs(450), Duration::ZERO), secs(450));
+    }
+
+    #[test]
+    fn unique_name_numbers_taken_names() {
+        assert_eq!(unique_name("Tea", &["Tea"]), "Tea (2)");
+        assert_eq!(unique_name("Tea", &["tea ", "Tea (2)"]), "Tea (3)");
+        assert_eq!(unique_name("Tea", &["Teapot"]), "Tea");
+        assert_eq!(unique_name("", &[""]), "");
+    }
+
+    fn fields(hours: &str, minutes: &str, seconds: &str) -> (String, String, String) {
+        (hours.to_string(), minutes.to_string(), seconds.to_string())
+    }
+
+    #[test]
+    fn shift_digits_fills_from_the_right() {
+        assert_eq!(shift_digits(""), fields("00", "00", "00"));
+        assert_eq!(shift_digits("5"), fields("00", "00", "05"));
+        assert_eq!(shift_digits("130"), fields("00", "01", "30"));
+        assert_eq!(shift_digits("1000"), fields("00", "10", "00"));
+        assert_eq!(shift_digits("90"), fields("00", "00", "90"));
+    }
+
+    #[test]
+    fn shift_digits_continues_from_the_formatted_fields() {
+        assert_eq!(shift_digits("001300"), fields("00", "13", "00"));
+        assert_eq!(shift_digits("0013005"), fields("01", "30", "05"));
+        assert_eq!(shift_digits("00:01:30"), fields("00", "01", "30"));
+    }
+
+    #[test]
+    fn shift_digits_drops_digits_past_three_for_hours() {
+        assert_eq!(shift_digits("12345678"), fields("234", "56", "78"));
+    }
+
+    #[test]
+    fn stopwatches_never_finish() {
+        let mut stopwatch = Timer {
+            mode: Mode::Stopwatch,
+            ..timer("00", "00", "00")
+        };
+        let started = start(&mut stopwatch);
+        for hours in [1, 99, 10_000] {
+            assert!(!stopwatch.tick(started + secs(hours * 3600)));
+        }
+        assert_eq!(stopwatch.state, State::Running);
+        assert_eq!(stopwatch.time, secs(10_000 * 3600));
+        assert_eq!(stopwatch.elapsed, secs(10_000 * 3600));
+    }
+
+    #[test]
+    fn starting_without_a_duration_stays_stopped() {
+        let mut timer = timer("00", "00", "00");
+        assert!(matches!(
+            timer.start(MAX_DURATION),
+            Err(StartError::NoDuration)
+        ));
+        assert_eq!(timer.state, State::Stopped);
+        assert!(timer.last_tick.is_none());
+    }
+
+    /// 2024-01-01 was a Monday
+    fn monday(hour: u32, minute: u32, second: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2024, 1, 1, hour, minute, second)
+            .unwrap()
+    }
+
+    fn schedule(days: &[usize], hour: u32, minute: u32) -> WeeklySchedule {
+        let mut schedule = WeeklySchedule {
+            at: hour * 60 + minute,
+            ..WeeklySchedule::default()
+        };
+        for &day in days {
+            schedule.days[day] = true;
+        }
+        schedule
+    }
+
+    #[test]
+    fn next_after_is_today_while_the_time_is_ahead() {
+        let schedule = schedule(&[0], 7, 0);
+        assert_eq!(schedule.next_after(monday(6, 0, 0)), Some(monday(7, 0, 0)));
+    }
+
+    #[test]
+    fn next_after_skips_today_once_the_time_passed() {
+        let schedule = schedule(&[0], 7, 0);
+        let next_week = monday(7, 0, 0) + chrono::TimeDelta::days(7);
+        assert_eq!(schedule.next_after(monday(8, 0, 0)), Some(next_week));
+        // Right on time counts as passed, it already went off
+        assert_eq!(schedule.next_after(monday(7, 0, 0)), Some(next_week));
+    }
+
+    #[test]
+    fn next_after_crosses_midnight() {
+        let tuesday = schedule(&[1], 0, 0);
+        let midnight = monday(0, 0, 0) + chrono::TimeDelta::days(1);
+        assert_eq!(tuesday.next_after(monday(23, 59, 30)), Some(midnight));
+        assert_eq!(schedule(&[], 7, 0).next_after(monday(8, 0, 0)), None);
+    }
+
+    #[test]
+    fn arm_schedule_counts_down_across_midnight() {
+        let mut timer = Timer {
+            schedule: Some(schedule(&[1], 0, 0)),
+            ..Timer::default()
+        };
+        assert!(timer.arm_schedule(monday(23, 59, 30)));
+        assert_eq!(timer.state, State::Running);
+        assert_eq!(timer.time, secs(30));
+    }
+
+    #[test]
+    fn arm_schedule_waits_a_week_when_today_passed() {
+        let mut timer = Timer {
+            schedule: Some(schedule(&[0], 7, 0)),
+            ..Timer::default()
+        };
+        assert!(timer.arm_schedule(monday(7, 30, 0)));
+        assert_eq!(timer.time, secs(7 * 24 * 3600 - 30 * 60));
+    }
+
+    #[test]
+    fn arm_schedule_needs_a_day_picked() {
+        let mut timer = Timer {
+            schedule: Some(schedule(&[], 7, 0)),
+            ..Timer::default()
+        };
+        assert!(!timer.arm_schedule(monday(6, 0, 0)));
+        assert_eq!(timer.state, State::Stopped);
+    }
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn clock_jumped_notices_the_wall_clock_moving_either_way() {
+        let mut timer = timer("00", "10", "00");
+        let started = start(&mut timer);
+        let wall = timer.ticked_at.unwrap();
+        let next_tick = started + secs(1);
+        assert!(!timer.clock_jumped(next_tick, wall + secs(1)));
+        assert!(timer.clock_jumped(next_tick, wall + HOUR));
+        assert!(timer.clock_jumped(next_tick, wall - HOUR));
+
+        timer.pause();
+        assert!(!timer.clock_jumped(next_tick, wall + HOUR));
+    }
+
+    #[test]
+    fn countdowns_go_by_the_monotonic_clock_across_a_jump() {
+        let mut timer = timer("00", "10", "00");
+        let started = start(&mut timer);
+        let wall = timer.ticked_at.unwrap();
+        assert!(timer.clock_jumped(started + secs(1), wall + HOUR));
+
+        let mut timers = vec![timer];
+        assert!(!rearm_schedules(&mut timers, monday(12, 0, 0)));
+        assert!(!timers[0].tick(started + secs(1)));
+        assert_eq!(timers[0].time, secs(599));
+    }
+
+    #[test]
+    fn rearm_schedules_follows_a_forward_jump() {
+        let mut timers = vec![Timer {
+            schedule: Some(schedule(&[0], 7, 0)),
+            ..Timer::default()
+        }];
+        assert!(timers[0].arm_schedule(monday(6, 0, 0)));
+        assert_eq!(timers[0].time, HOUR);
+
+        assert!(rearm_schedules(&mut timers, monday(6, 50, 0)));
+        assert_eq!(timers[0].time, secs(10 * 60));
+    }
+
+    #[test]
+    fn rearm_schedules_follows_a_backward_jump() {
+        let mut timers = vec![Timer {
+            schedule: Some(schedule(&[0], 7, 0)),
+            ..Timer::default()
+        }];
+        assert!(timers[0].arm_schedule(monday(6, 0, 0)));
+
+        assert!(rearm_schedules(&mut timers, monday(5, 0, 0)));
+        assert_eq!(timers[0].time, 2 * HOUR);
+    }
+}
@@ -1,9 +1,382 @@
-use std::fmt::Display;
+use iced::futures::channel::oneshot;
+use std::{
+    fmt::Display,
+    future::Future,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, OnceLock,
+    },
+    time::{Duration, SystemTime},
+};
+
+use crate::{
+    config::{NotificationTimeout, Urgency},
+    i18n::{t, t_fmt},
+    timer::Timer,
+};
+
+const BUNDLED_SOUND: &[u8] = include_bytes!("../resources/sounds/lofi-alarm-clock.mp3");
+
+static STATE_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Where this machine keeps its state. Logs and the instance socket always stay
+/// here, even when the timers are synced through another directory.
+pub fn local_state_dir() -> PathBuf {
+    // Since I don't care about Windows
+    #[allow(deprecated)]
+    let mut path = std::env::home_dir().unwrap_or_default();
+    path.push(std::path::Path::new(".local/state/oxyclock"));
+    path
+}
+
+/// Moves timers, templates and history to `OXYCLOCK_STATE_DIR`, or to `dir`
+/// from the config, e.g. a folder synced between machines
+pub fn init_state_dir(dir: Option<PathBuf>) {
+    let Some(dir) = std::env::var_os("OXYCLOCK_STATE_DIR")
+        .map(PathBuf::from)
+        .or(dir)
+    else {
+        return;
+    };
+    if let Err(err) = check_writable(&dir) {
+        log::warn!("state directory {} isn't writable: {err}", dir.display());
+    }
+    let _ = STATE_DIR.set(dir);
+}
+
+fn check_writable(dir: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    let probe = dir.join(format!(".oxyclock-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(probe)
+}
+
+pub fn state_dir() -> PathBuf {
+    STATE_DIR.get().cloned().unwrap_or_else(local_state_dir)
+}
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Timers of `profile`. The default profile keeps the `state.json` from before
+/// there were profiles, the others live in `state/<profile>.json`.
+pub fn state_path(profile: &str) -> PathBuf {
+    if profile == DEFAULT_PROFILE {
+        state_dir().join("state.json")
+    } else {
+        state_dir().join("state").join(format!("{profile}.json"))
+    }
+}
+
+/// The default profile followed by the ones with a state file, by name
+pub fn profiles() -> Vec<String> {
+    let mut profiles: Vec<String> = std::fs::read_dir(state_dir().join("state"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            path.file_stem()?.to_str().map(str::to_string)
+        })
+        .filter(|profile| profile != DEFAULT_PROFILE)
+        .collect();
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    profiles
+}
+
+/// Profile names end up in file names, so only keep what's safe there
+pub fn sanitize_profile(name: &str) -> String {
+    name.trim()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        .collect()
+}
+
+/// Desktop notification for a timer that just ran out, unless it's silent or
+/// doesn't notify. `break_started` means it went on to its break.
+pub fn notify_finished(
+    timer: &Timer,
+    name: &str,
+    break_over: bool,
+    break_started: bool,
+    urgency: Urgency,
+    timeout: NotificationTimeout,
+) {
+    if !timer.notify || timer.silent {
+        return;
+    }
+
+    // An image file goes next to the text, anything else is put in front of the name
+    let image = timer
+        .icon
+        .as_deref()
+        .filter(|icon| Path::new(icon).is_file());
+    let name = match (&timer.icon, image) {
+        (Some(icon), None) => format!("{} {name}", icon.trim()),
+        _ => name.to_string(),
+    };
+    let (summary, body) = finished_message(timer, &name, break_over, break_started);
+    let mut notification = notify_rust::Notification::new();
+    if let Some(image) = image {
+        notification.icon(image);
+    }
+    notification
+        .summary(&summary)
+        .body(&body)
+        .appname("oxyclock")
+        .timeout(match timeout {
+            NotificationTimeout::Auto => notify_rust::Timeout::Default,
+            NotificationTimeout::Seconds(seconds) => {
+                notify_rust::Timeout::Milliseconds(seconds.saturating_mul(1000))
+            }
+            NotificationTimeout::Sticky => notify_rust::Timeout::Never,
+        });
+    // Only freedesktop notifications know about urgency
+    #[cfg(all(unix, not(target_os = "macos")))]
+    notification.urgency(match urgency {
+        Urgency::Low => notify_rust::Urgency::Low,
+        Urgency::Normal => notify_rust::Urgency::Normal,
+        Urgency::Critical => notify_rust::Urgency::Critical,
+    });
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    let _ = urgency;
+    if let Err(err) = notification.show() {
+        log::warn!("failed to send notification: {err}");
+    }
+}
+
+/// Summary and body of the notification. Timers with a break say what comes
+/// next, plain countdowns keep the short body.
+fn finished_message(
+    timer: &Timer,
+    name: &str,
+    break_over: bool,
+    break_started: bool,
+) -> (String, String) {
+    // `duration` still holds the length of the run the break belongs to
+    let next = format_hms(timer.duration);
+    if break_over {
+        (
+            t_fmt("break_over", &[("name", name)]),
+            t_fmt("break_over_body", &[("name", name), ("duration", &next)]),
+        )
+    } else if break_started {
+        (
+            t_fmt("timer_done", &[("name", name)]),
+            t_fmt(
+                "take_a_break",
+                &[
+                    ("duration", &format_hms(timer.time)),
+                    ("name", name),
+                    ("next", &next),
+                ],
+            ),
+        )
+    } else {
+        (
+            t_fmt("timer_done", &[("name", name)]),
+            t("timer_done_body").to_string(),
+        )
+    }
+}
+
+/// Says how much time is left through speech-dispatcher, without waiting for it
+pub fn announce(remaining: Duration) {
+    let secs = remaining.as_secs();
+    let text = match secs {
+        60 => t("minute_remaining").to_string(),
+        _ if secs >= 60 && secs % 60 == 0 => {
+            t_fmt("minutes_remaining", &[("count", &(secs / 60).to_string())])
+        }
+        1 => t("second_remaining").to_string(),
+        _ => t_fmt("seconds_remaining", &[("count", &secs.to_string())]),
+    };
+    match std::process::Command::new("spd-say").arg(&text).spawn() {
+        // Reaped from a thread so finished announcements don't linger as zombies
+        Ok(mut child) => drop(std::thread::spawn(move || child.wait())),
+        Err(err) => {
+            log::warn!("failed to announce \"{text}\", is speech-dispatcher installed? {err}")
+        }
+    }
+}
+
+/// Whether the session is locked or idle, going by systemd-logind. `None` where
+/// that can't be told: no logind, not in a logind session, or not on Linux.
+/// Idle is only known on desktops that report it to logind, like GNOME.
+pub fn session_away() -> impl Future<Output = Option<bool>> {
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+        let output = std::process::Command::new("loginctl")
+            .args(["show-session", &session])
+            .args(["--property=LockedHint", "--property=IdleHint", "--value"])
+            .output();
+        let away = match output {
+            Ok(output) if output.status.success() => {
+                let hints = String::from_utf8_lossy(&output.stdout);
+                Some(hints.lines().any(|hint| hint.trim() == "yes"))
+            }
+            Ok(output) => {
+                log::debug!(
+                    "loginctl failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                );
+                None
+            }
+            Err(err) => {
+                log::debug!("failed to run loginctl: {err}");
+                None
+            }
+        };
+        let _ = sender.send(away);
+    });
+
+    async move { receiver.await.unwrap_or(None) }
+}
+
+/// Advisory lock on `state.json.lock`, held until the returned file is dropped.
+/// Writers take it exclusively and readers shared, so saves never interleave.
+pub fn lock_state(exclusive: bool) -> std::io::Result<std::fs::File> {
+    use fs2::FileExt;
+
+    std::fs::create_dir_all(state_dir())?;
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(state_dir().join("state.json.lock"))?;
+    if exclusive {
+        file.lock_exclusive()?;
+    } else {
+        file.lock_shared()?;
+    }
+    Ok(file)
+}
+
+pub use crate::timer::format_hms;
+
+/// A timer typed into the quick-add bar, e.g. "Tea 3:00" or "Standup 1h 30m start"
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickAdd {
+    pub name: String,
+    pub duration: Duration,
+    pub start: bool,
+}
+
+/// Splits "NAME DURATION [start]" where the name may be empty and the duration is
+/// anything `parse_duration` accepts. The longest trailing duration wins, so
+/// "Tea 1h 30m" is named "Tea" rather than "Tea 1h".
+pub fn parse_quick_add(input: &str) -> Option<QuickAdd> {
+    let mut words: Vec<&str> = input.split_whitespace().collect();
+    let start = words
+        .last()
+        .is_some_and(|word| word.eq_ignore_ascii_case("start"));
+    if start {
+        words.pop();
+    }
+
+    (0..words.len()).find_map(|split| {
+        let duration = parse_duration(&words[split..].join(" "))?;
+        Some(QuickAdd {
+            name: words[..split].join(" "),
+            duration,
+            start,
+        })
+    })
+}
+
+/// "5 min ago", "3 days ago"... in the largest whole unit. Times in the future,
+/// from a clock that was set back, read as just now.
+pub fn format_ago(then: SystemTime, now: SystemTime) -> String {
+    let Ok(passed) = now.duration_since(then) else {
+        return t("just_now").to_string();
+    };
+    let secs = passed.as_secs();
+    let (key, count) = match secs {
+        0..=59 => ("seconds_ago", secs),
+        60..=3599 => ("minutes_ago", secs / 60),
+        3600..=86_399 => ("hours_ago", secs / 3600),
+        86_400..=172_799 => return t("day_ago").to_string(),
+        _ => ("days_ago", secs / 86_400),
+    };
+    t_fmt(key, &[("count", &count.to_string())])
+}
+
+/// Wall-clock time like "15:42" or "3:42 PM", with the date in front when it
+/// isn't today
+pub fn format_clock(at: SystemTime, now: SystemTime, clock_24h: bool) -> String {
+    let at: chrono::DateTime<chrono::Local> = at.into();
+    let now: chrono::DateTime<chrono::Local> = now.into();
+    let time = at.format(if clock_24h { "%H:%M" } else { "%-I:%M %p" });
+    if at.date_naive() == now.date_naive() {
+        time.to_string()
+    } else {
+        format!("{} {time}", at.format("%b %-d"))
+    }
+}
+
+/// Parses "HH:MM:SS", "MM:SS" or human text like "1h 30m", "25 min" and "90s".
+/// A bare number is taken as minutes.
+pub fn parse_duration(input: &str) -> Option<Duration> {
+    let input = input.trim().to_lowercase();
+    if input.is_empty() {
+        return None;
+    }
+
+    if input.contains(':') {
+        let parts = input
+            .split(':')
+            .map(|part| part.trim().parse::<u64>().ok())
+            .collect::<Option<Vec<_>>>()?;
+        let total_secs = match parts.as_slice() {
+            [minutes, seconds] => minutes.checked_mul(60)?.checked_add(*seconds)?,
+            [hours, minutes, seconds] => hours
+                .checked_mul(3600)?
+                .checked_add(minutes.checked_mul(60)?)?
+                .checked_add(*seconds)?,
+            _ => return None,
+        };
+        return Some(Duration::from_secs(total_secs));
+    }
+
+    if let Ok(minutes) = input.parse::<u64>() {
+        return Some(Duration::from_secs(minutes.checked_mul(60)?));
+    }
+
+    let mut total_secs = 0u64;
+    let mut rest = input.as_str();
+    while !rest.trim_start().is_empty() {
+        rest = rest.trim_start();
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let value = rest[..digits].parse::<u64>().ok()?;
+        rest = rest[digits..].trim_start();
+        let letters = rest
+            .find(|c: char| !c.is_alphabetic())
+            .unwrap_or(rest.len());
+        let multiplier = match &rest[..letters] {
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3600,
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "s" | "sec" | "secs" | "second" | "seconds" => 1,
+            _ => return None,
+        };
+        rest = &rest[letters..];
+        total_secs = total_secs.checked_add(value.checked_mul(multiplier)?)?;
+    }
+
+    Some(Duration::from_secs(total_secs))
+}
 
 pub enum NotificationError {
     PlayError(rodio::PlayError),
     StreamError(rodio::StreamError),
     FsError(std::io::Error),
+    DecodeError(rodio::decoder::DecoderError),
 }
 
 impl Display for NotificationError {
@@ -12,20 +385,305 @@ impl Display for NotificationError {
             Self::PlayError(err) => write!(f, "{err}"),
             Self::StreamError(err) => write!(f, "{err}"),
             Self::FsError(err) => write!(f, "{err}"),
+            Self::DecodeError(err) => write!(f, "{err}"),
         }
     }
 }
 
-pub fn play_notification_sound() -> Result<(), NotificationError> {
+/// Cloneable outcome of a failed playback that can travel inside a `Msg`
+#[derive(Debug, Clone)]
+pub enum SoundError {
+    NoDevice,
+    BadSound(String),
+    /// The playback thread died before reporting back
+    Crashed,
+}
+
+impl From<NotificationError> for SoundError {
+    fn from(err: NotificationError) -> Self {
+        match err {
+            NotificationError::StreamError(_) => Self::NoDevice,
+            err => Self::BadSound(err.to_string()),
+        }
+    }
+}
+
+impl Display for SoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoDevice => write!(f, "{}", t("no_audio_output")),
+            Self::BadSound(err) => write!(f, "{}", t_fmt("sound_failed", &[("error", err)])),
+            Self::Crashed => write!(f, "{}", t("sound_crashed")),
+        }
+    }
+}
+
+/// Stops a looping alarm from another thread, dropping every clone doesn't
+#[derive(Debug, Clone, Default)]
+pub struct AlarmHandle(Arc<AtomicBool>);
+
+impl AlarmHandle {
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How many times a sound plays back to back
+#[derive(Debug, Clone)]
+pub enum Repeat {
+    Times(u32),
+    UntilStopped(AlarmHandle),
+}
+
+/// Plays `path`, or the bundled alarm when unset. A custom sound that can't be
+/// played is still an error, returned once the bundled one rang in its place.
+pub fn play_notification_sound(
+    path: Option<&std::path::Path>,
+    volume: f32,
+    repeat: &Repeat,
+) -> Result<(), NotificationError> {
     let (_stream, stream_handle) =
         rodio::OutputStream::try_default().map_err(NotificationError::StreamError)?;
-    let file = std::io::BufReader::new(
-        std::fs::File::open("/usr/share/sounds/lofi-alarm-clock.mp3")
-            .map_err(NotificationError::FsError)?,
-    );
     let sink = rodio::Sink::try_new(&stream_handle).map_err(NotificationError::PlayError)?;
-    let source = rodio::Decoder::new_mp3(file).unwrap();
-    sink.append(source);
-    sink.sleep_until_end();
-    Ok(())
+    sink.set_volume(volume);
+    // A custom sound wins, the bundled one covers unset, missing or broken files.
+    // The alarm still rings for a broken file, but the caller hears about it.
+    let custom = path.map(|path| {
+        decode_file(path).inspect_err(|err| {
+            log::warn!(
+                "can't play {}, using the bundled alarm: {err}",
+                path.display()
+            )
+        })
+    });
+    let fallback = match custom {
+        Some(Ok(source)) => {
+            append_repeated(&sink, source, repeat);
+            None
+        }
+        broken => {
+            let source = rodio::Decoder::new_mp3(std::io::Cursor::new(BUNDLED_SOUND))
+                .map_err(NotificationError::DecodeError)?;
+            append_repeated(&sink, source, repeat);
+            broken.and_then(Result::err)
+        }
+    };
+
+    match repeat {
+        Repeat::Times(_) => sink.sleep_until_end(),
+        Repeat::UntilStopped(alarm) => {
+            while !alarm.is_stopped() {
+                std::thread::sleep(Duration::from_millis(50));
+            }
+            sink.stop();
+        }
+    }
+    fallback.map_or(Ok(()), Err)
+}
+
+fn decode_file(
+    path: &Path,
+) -> Result<rodio::Decoder<std::io::BufReader<std::fs::File>>, NotificationError> {
+    let file =
+        std::io::BufReader::new(std::fs::File::open(path).map_err(NotificationError::FsError)?);
+    rodio::Decoder::new_mp3(file).map_err(NotificationError::DecodeError)
+}
+
+/// Whether `path` can be played, files are only read when an alarm goes off
+/// and may have been moved or deleted since they were picked
+pub fn check_sound(path: &Path) -> Result<(), NotificationError> {
+    decode_file(path).map(|_| ())
+}
+
+fn append_repeated<S>(sink: &rodio::Sink, source: S, repeat: &Repeat)
+where
+    S: rodio::Source + Send + 'static,
+    S::Item: rodio::Sample + Send,
+{
+    use rodio::Source;
+
+    match repeat {
+        Repeat::Times(times) => {
+            let source = source.buffered();
+            for _ in 0..(*times).max(1) {
+                sink.append(source.clone());
+            }
+        }
+        Repeat::UntilStopped(_) => sink.append(source.repeat_infinite()),
+    }
+}
+
+/// Plays the sound on its own thread and resolves once playback is over
+pub fn play_notification_sound_in_background(
+    path: Option<PathBuf>,
+    volume: f32,
+    repeat: Repeat,
+) -> impl Future<Output = Result<(), SoundError>> {
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let result =
+            play_notification_sound(path.as_deref(), volume, &repeat).map_err(SoundError::from);
+        let _ = sender.send(result);
+    });
+
+    // The sender only goes away without a result when the thread panicked
+    async move { receiver.await.unwrap_or(Err(SoundError::Crashed)) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_sound_removed_after_picking_it_fails_the_check() {
+        let path = std::env::temp_dir().join(format!("oxyclock-sound-{}.mp3", std::process::id()));
+        std::fs::write(&path, BUNDLED_SOUND).unwrap();
+        assert!(check_sound(&path).is_ok());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(
+            check_sound(&path),
+            Err(NotificationError::FsError(_))
+        ));
+    }
+
+    fn finished(duration: u64, time: u64) -> Timer {
+        Timer {
+            duration: Duration::from_secs(duration),
+            time: Duration::from_secs(time),
+            ..Timer::default()
+        }
+    }
+
+    #[test]
+    fn finished_message_without_a_break() {
+        let (title, body) = finished_message(&finished(1500, 0), "Focus", false, false);
+        assert_eq!(title, "Focus is done!");
+        assert_eq!(body, "Your timer has finished");
+    }
+
+    #[test]
+    fn finished_message_when_a_break_starts() {
+        let (title, body) = finished_message(&finished(1500, 300), "Focus", false, true);
+        assert_eq!(title, "Focus is done!");
+        assert_eq!(
+            body,
+            "Take a break for 00:05:00, then Focus again for 00:25:00"
+        );
+    }
+
+    #[test]
+    fn finished_message_when_the_break_is_over() {
+        let (title, body) = finished_message(&finished(1500, 0), "Focus", true, false);
+        assert_eq!(title, "Break after Focus is over");
+        assert_eq!(
+            body,
+            "Time to get back to it, next up is Focus for 00:25:00"
+        );
+    }
+
+    #[test]
+    fn format_ago_picks_the_largest_whole_unit() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let ago = |secs| format_ago(now - Duration::from_secs(secs), now);
+        assert_eq!(ago(0), "0 s ago");
+        assert_eq!(ago(59), "59 s ago");
+        assert_eq!(ago(60), "1 min ago");
+        assert_eq!(ago(3599), "59 min ago");
+        assert_eq!(ago(3600), "1 h ago");
+        assert_eq!(ago(86_399), "23 h ago");
+        assert_eq!(ago(86_400), "1 day ago");
+        assert_eq!(ago(172_800), "2 days ago");
+    }
+
+    #[test]
+    fn format_ago_reads_the_future_as_just_now() {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        assert_eq!(format_ago(now + Duration::from_secs(90), now), "Just now");
+    }
+
+    #[test]
+    fn parse_duration_reads_clock_and_human_forms() {
+        let cases = [
+            ("25:00", 1500),
+            ("1:30:00", 5400),
+            ("0:90", 90),
+            (" 25 ", 1500),
+            ("1h 30m", 5400),
+            ("1H30M", 5400),
+            ("25 min", 1500),
+            ("2 hours 5 seconds", 7205),
+            ("90s", 90),
+        ];
+        for (input, secs) in cases {
+            assert_eq!(
+                parse_duration(input),
+                Some(Duration::from_secs(secs)),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        for input in [
+            "18446744073709551615:00",
+            "5124095576030432:00:00",
+            "307445734561825861",
+            "99999999999999999999 min",
+            "5124095576030432h",
+        ] {
+            assert_eq!(parse_duration(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_duration_rejects_garbage() {
+        for input in [
+            "",
+            "   ",
+            "abc",
+            "h",
+            "1.5h",
+            "5 parsecs",
+            "1:2:3:4",
+            "1:xx",
+            ":",
+        ] {
+            assert_eq!(parse_duration(input), None, "{input}");
+        }
+    }
+
+    #[test]
+    fn parse_quick_add_splits_name_and_duration() {
+        let cases = [
+            ("Tea 3:00", "Tea", 180, false),
+            ("Standup 1h 30m start", "Standup", 5400, true),
+            ("Pasta al dente 9 min", "Pasta al dente", 540, false),
+            ("25", "", 1500, false),
+            ("Nap 20 START", "Nap", 1200, true),
+        ];
+        for (input, name, secs, start) in cases {
+            assert_eq!(
+                parse_quick_add(input),
+                Some(QuickAdd {
+                    name: name.to_string(),
+                    duration: Duration::from_secs(secs),
+                    start,
+                }),
+                "{input}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_quick_add_needs_a_duration() {
+        for input in ["", "Tea", "start", "Tea start", "Tea 99999999999999999999"] {
+            assert_eq!(parse_quick_add(input), None, "{input}");
+        }
+    }
 }
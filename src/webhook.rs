@@ -0,0 +1,95 @@
+use iced::Task;
+use std::time::SystemTime;
+
+use crate::{timer::Timer, Msg};
+
+/// A POST of `{ name, duration, finished_at }` to the webhook of a finished timer.
+/// Builds without the `webhook` feature keep the URL around but never send it.
+pub struct Call {
+    url: String,
+    #[cfg_attr(not(feature = "webhook"), allow(dead_code))]
+    body: serde_json::Value,
+}
+
+impl Call {
+    /// `None` when the timer has no webhook. Made before a break starts over the
+    /// time the run took.
+    pub fn new(timer: &Timer, name: &str, finished_at: SystemTime) -> Option<Self> {
+        let url = timer.webhook.clone().filter(|url| !url.trim().is_empty())?;
+        let finished_at: chrono::DateTime<chrono::Local> = finished_at.into();
+        let body = serde_json::json!({
+            "name": name,
+            "duration": timer.elapsed.as_secs(),
+            "finished_at": finished_at.to_rfc3339_opts(chrono::SecondsFormat::Secs, false),
+        });
+        Some(Self { url, body })
+    }
+
+    /// Calls from the window, the result comes back as `Msg::WebhookSent`
+    pub fn task(self) -> Task<Msg> {
+        #[cfg(feature = "webhook")]
+        {
+            Task::perform(post(self.url, self.body), Msg::WebhookSent)
+        }
+
+        #[cfg(not(feature = "webhook"))]
+        {
+            self.skip();
+            Task::none()
+        }
+    }
+
+    /// Calls from a thread of its own for the daemon, which has no async runtime.
+    /// A failure is only logged.
+    pub fn spawn(self) {
+        #[cfg(feature = "webhook")]
+        std::thread::spawn(move || {
+            if let Err(err) = post_blocking(&self.url, &self.body) {
+                log::warn!("webhook failed: {err}");
+            }
+        });
+
+        #[cfg(not(feature = "webhook"))]
+        self.skip();
+    }
+
+    #[cfg(not(feature = "webhook"))]
+    fn skip(self) {
+        log::warn!(
+            "not calling {}, oxyclock was built without the webhook feature",
+            self.url
+        );
+    }
+}
+
+/// A single attempt, a failed call is only logged
+#[cfg(feature = "webhook")]
+async fn post(url: String, body: serde_json::Value) -> Result<(), String> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|err| err.to_string())?;
+    client
+        .post(&url)
+        .json(&body)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map(|_| ())
+        .map_err(|err| format!("{url}: {err}"))
+}
+
+#[cfg(feature = "webhook")]
+fn post_blocking(url: &str, body: &serde_json::Value) -> Result<(), String> {
+    let client = reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(10))
+        .build()
+        .map_err(|err| err.to_string())?;
+    client
+        .post(url)
+        .json(body)
+        .send()
+        .and_then(reqwest::blocking::Response::error_for_status)
+        .map(|_| ())
+        .map_err(|err| format!("{url}: {err}"))
+}